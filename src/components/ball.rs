@@ -3,11 +3,10 @@ use amethyst::ecs::prelude::{
     DenseVecStorage
 };
 
-/// The Ball component contains data that defines a ball on the field, such as the radius
-/// and the velocity.
+/// The Ball component links a ball entity to its `BallBody` inside the `Simulation`, which holds
+/// the actual data such as the radius and the velocity.
 pub struct Ball {
-    pub velocity: [f32; 2],
-    pub radius: f32,
+    pub index: usize,
 }
 
 impl Component for Ball {
@@ -2,7 +2,20 @@ use amethyst::core::math::Vector2;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::resources::{
+    GamepadAxis,
+    GamepadButton,
+    Player,
+};
+
+/// Path of the shipped key bindings, relative to the application root.
+pub const INPUT_CONFIG_PATH: &str = "resources/input.ron";
+
+/// Path of the key bindings changed on the controls screen, relative to the application root.
+/// If present, they are used instead of the shipped key bindings.
+pub const USER_INPUT_CONFIG_PATH: &str = "resources/input.user.ron";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ArenaConfig {
     pub height: f32,
     pub width: f32,
@@ -17,10 +30,14 @@ impl Default for ArenaConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BallConfig {
     pub velocity: Vector2<f32>,
     pub radius: f32,
+    /// Describes how the speed of the ball increases with every paddle hit of a rally.
+    pub speed_curve: SpeedCurve,
+    /// Speed in units per second the ball never exceeds, no matter how long the rally is.
+    pub max_speed: f32,
 }
 
 impl Default for BallConfig {
@@ -28,14 +45,51 @@ impl Default for BallConfig {
         BallConfig {
             velocity: Vector2::new(75.0, 50.0),
             radius: 2.5,
+            speed_curve: SpeedCurve::Exponential(1.05),
+            max_speed: 200.0,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// SpeedCurve enumeration describes how the speed of the ball changes on a paddle hit.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum SpeedCurve {
+    /// The speed is increased by a fixed amount of units per second.
+    Linear(f32),
+    /// The speed is multiplied by a fixed factor.
+    Exponential(f32),
+}
+
+impl SpeedCurve {
+    /// Returns the speed after the next paddle hit, capped at `max_speed`.
+    pub fn next_speed(&self, speed: f32, max_speed: f32) -> f32 {
+        let next = match *self {
+            SpeedCurve::Linear(increment) => speed + increment,
+            SpeedCurve::Exponential(factor) => speed * factor,
+        };
+        next.min(max_speed)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PaddleConfig {
     pub height: f32,
     pub width: f32,
+    /// Maximum speed of the paddle in units per second.
+    pub speed: f32,
+    pub movement: PaddleMovement,
+    /// Rate in units per second² at which the paddle speeds up towards the requested velocity
+    /// (or slows down, when changing direction). Only used by `PaddleMovement::Momentum`.
+    pub acceleration: f32,
+    /// Rate in units per second² at which the paddle slows down without input. Only used by
+    /// `PaddleMovement::Momentum`.
+    pub friction: f32,
+    /// Angle in degrees at which the ball leaves the paddle when hit at the very edge. Hits at the
+    /// centre of the paddle send the ball back flat.
+    pub max_bounce_angle: f32,
+    /// Share of the paddle's vertical velocity that is passed on to the ball on a hit, also
+    /// known as "english". Set to 0.0 to disable.
+    pub english: f32,
 }
 
 impl Default for PaddleConfig {
@@ -43,13 +97,407 @@ impl Default for PaddleConfig {
         PaddleConfig {
             height: 15.0,
             width: 2.5,
+            speed: 72.0,
+            movement: PaddleMovement::Direct,
+            acceleration: 600.0,
+            friction: 400.0,
+            max_bounce_angle: 60.0,
+            english: 0.25,
+        }
+    }
+}
+
+/// PaddleMovement enumeration describes how the input of a player moves their paddle.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PaddleMovement {
+    /// The input is the velocity of the paddle, it starts and stops instantly.
+    Direct,
+    /// The input is the velocity the paddle accelerates towards, it keeps moving and slows down
+    /// by friction once the input is released.
+    Momentum,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SimulationConfig {
+    /// Number of fixed simulation ticks per second.
+    pub tick_rate: u32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            tick_rate: 120,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ServeConfig {
+    /// Where the ball waits until it is launched by the serving player.
+    pub position: ServePosition,
+    /// Who serves after a point was scored.
+    pub rule: ServeRule,
+    /// Seconds after which the ball is launched automatically. Set to 0.0 to wait forever.
+    pub timeout: f32,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        ServeConfig {
+            position: ServePosition::Paddle,
+            rule: ServeRule::Alternate,
+            timeout: 3.0,
+        }
+    }
+}
+
+/// ServePosition enumeration describes where the ball is placed during a serve.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ServePosition {
+    /// In front of the serving player's paddle, following its movement.
+    Paddle,
+    /// In the centre of the arena.
+    Centre,
+}
+
+/// ServeRule enumeration describes which player serves after a point was scored.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ServeRule {
+    /// The players take turns.
+    Alternate,
+    /// The player who lost the point.
+    Loser,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MatchConfig {
+    /// Points required to win a game.
+    pub points_to_win: u32,
+    /// Whether a game has to be won by a lead of two points. When both players reach
+    /// `points_to_win - 1` points, the game goes into deuce.
+    pub win_by_two: bool,
+    /// Maximum number of games of a match. The first player to win the majority of the games
+    /// wins the match. Games take the role of sets, there is no further level between a game and
+    /// the match.
+    pub best_of: u32,
+}
+
+impl MatchConfig {
+    /// Returns the number of games required to win the match.
+    pub fn games_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+}
+
+impl Default for MatchConfig {
+    fn default() -> Self {
+        MatchConfig {
+            points_to_win: 11,
+            win_by_two: true,
+            best_of: 3,
+        }
+    }
+}
+
+/// Difficulty enumeration describes how well the ai plays in single player matches.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Impossible,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Impossible,
+            Difficulty::Impossible => Difficulty::Easy,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Impossible,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+            Difficulty::Impossible => Difficulty::Hard,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Impossible => "Impossible",
         }
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+/// AiMode enumeration describes how the ai chooses where to move its paddle.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AiMode {
+    /// Follow the ball, anticipating its movement in a straight line.
+    Track,
+    /// Predict where the ball crosses the paddle, including the bounces off the top and bottom
+    /// of the arena, and move there early. While the ball travels away, return to the centre.
+    Intercept,
+}
+
+/// AiLevel holds the parameters of a single `Difficulty`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AiLevel {
+    pub mode: AiMode,
+    /// Seconds it takes the ai to react to the ball, i.e. it always sees the ball where it was
+    /// that long ago.
+    pub reaction_time: f32,
+    /// Share of the paddle speed the ai moves with, between 0.0 and 1.0.
+    pub max_speed: f32,
+    /// How far ahead the ai anticipates the ball, between 0.0 (it follows the current position
+    /// of the ball) and 1.0 (it moves to where the ball is going to reach its paddle).
+    pub prediction: f32,
+    /// Maximum distance in units by which the ai misses its target. A new random error is chosen
+    /// for every ball coming towards the paddle of the ai.
+    pub aim_error: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AiConfig {
+    /// The difficulty used for the next single player match.
+    pub difficulty: Difficulty,
+    /// The player the human plays in the next single player match, the ai plays the other one.
+    pub human: Player,
+    pub easy: AiLevel,
+    pub normal: AiLevel,
+    pub hard: AiLevel,
+    pub impossible: AiLevel,
+}
+
+impl AiConfig {
+    /// Returns the parameters of the selected difficulty.
+    pub fn level(&self) -> &AiLevel {
+        match self.difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Hard => &self.hard,
+            Difficulty::Impossible => &self.impossible,
+        }
+    }
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        AiConfig {
+            difficulty: Difficulty::Normal,
+            human: Player::P1,
+            easy: AiLevel {
+                mode: AiMode::Track,
+                reaction_time: 0.3,
+                max_speed: 0.5,
+                prediction: 0.0,
+                aim_error: 8.0,
+            },
+            normal: AiLevel {
+                mode: AiMode::Track,
+                reaction_time: 0.15,
+                max_speed: 0.75,
+                prediction: 0.5,
+                aim_error: 5.0,
+            },
+            hard: AiLevel {
+                mode: AiMode::Intercept,
+                reaction_time: 0.08,
+                max_speed: 1.0,
+                prediction: 0.85,
+                aim_error: 3.0,
+            },
+            impossible: AiLevel {
+                mode: AiMode::Intercept,
+                reaction_time: 0.0,
+                max_speed: 1.0,
+                prediction: 1.0,
+                aim_error: 0.0,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttractConfig {
+    /// Seconds without any key press on the menu, after which the ai starts playing against
+    /// itself behind the menu. Set to 0.0 to disable.
+    pub idle_time: f32,
+}
+
+impl Default for AttractConfig {
+    fn default() -> Self {
+        AttractConfig {
+            idle_time: 20.0,
+        }
+    }
+}
+
+/// GamepadConfig holds the gamepad bindings, which are the same for both players.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GamepadConfig {
+    /// Axis moving the paddle. The d-pad moves the paddle as well.
+    pub movement_axis: GamepadAxis,
+    /// Whether pushing the axis up moves the paddle down.
+    pub invert_movement: bool,
+    /// Axis values closer to 0.0 are ignored, so sticks which don't rest exactly at the centre
+    /// don't move the paddle.
+    pub dead_zone: f32,
+    pub launch_button: GamepadButton,
+    /// Button pausing and resuming the game.
+    pub pause_button: GamepadButton,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        GamepadConfig {
+            movement_axis: GamepadAxis::LeftStickY,
+            invert_movement: false,
+            dead_zone: 0.15,
+            launch_button: GamepadButton::South,
+            pause_button: GamepadButton::Start,
+        }
+    }
+}
+
+/// MultiBallConfig holds the rules of matches with several balls in play at the same time.
+/// Every ball scores on its own.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MultiBallConfig {
+    /// Number of balls in play. Set to 1 for classic matches.
+    pub balls: u32,
+    /// When a ball that left the arena is served again.
+    pub respawn: RespawnRule,
+    /// Angle in degrees between the directions of balls which are served together.
+    pub spread: f32,
+}
+
+impl Default for MultiBallConfig {
+    fn default() -> Self {
+        MultiBallConfig {
+            balls: 1,
+            respawn: RespawnRule::Immediately,
+            spread: 15.0,
+        }
+    }
+}
+
+/// RespawnRule enumeration describes when a ball that left the arena is served again.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RespawnRule {
+    /// Right away, by the player whose turn it is to serve.
+    Immediately,
+    /// Once the last ball left the arena, all balls are served together.
+    LastBall,
+}
+
+/// NetworkConfig holds the settings of matches over the network. The joining player plays with
+/// the `GameConfig` of the host, only their own network settings apply to them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NetworkConfig {
+    /// UDP port the host waits for a player to join on.
+    pub port: u16,
+    /// Address of the host to join, e.g. "192.168.0.2:7777".
+    pub address: String,
+    /// Number of ticks the local inputs are delayed by, which hides that much latency without
+    /// having to correct the predicted inputs of the remote player.
+    pub input_delay: u64,
+    /// Maximum number of ticks the inputs of the remote player are predicted for, before the
+    /// game waits for them.
+    pub max_prediction: u64,
+    /// Fraction of the sent packets that are dropped on purpose, to test bad connections, e.g.
+    /// with two instances on localhost.
+    pub packet_loss: f32,
+    /// Seconds every sent packet is delayed by on purpose.
+    pub latency: f32,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            port: 7777,
+            address: "127.0.0.1:7777".to_string(),
+            input_delay: 2,
+            max_prediction: 16,
+            packet_loss: 0.0,
+            latency: 0.0,
+        }
+    }
+}
+
+/// MouseConfig holds which player, if any, controls their paddle with the mouse.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MouseConfig {
+    /// The player whose paddle follows the mouse cursor. Ignored while the player is played by
+    /// the ai.
+    pub player: Option<Player>,
+}
+
+impl MouseConfig {
+    /// Switches to the next option: off, player 1, player 2.
+    pub fn next(&mut self) {
+        self.player = match self.player {
+            None => Some(Player::P1),
+            Some(Player::P1) => Some(Player::P2),
+            Some(Player::P2) => None,
+        };
+    }
+
+    /// Switches to the previous option: off, player 1, player 2.
+    pub fn previous(&mut self) {
+        self.player = match self.player {
+            None => Some(Player::P2),
+            Some(Player::P1) => None,
+            Some(Player::P2) => Some(Player::P1),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct GameConfig {
+    pub ai: AiConfig,
     pub arena: ArenaConfig,
+    pub attract: AttractConfig,
     pub ball: BallConfig,
+    pub gamepad: GamepadConfig,
+    pub mouse: MouseConfig,
+    pub multi_ball: MultiBallConfig,
+    pub network: NetworkConfig,
     pub paddle: PaddleConfig,
+    #[serde(rename = "match")]
+    pub match_rules: MatchConfig,
+    pub serve: ServeConfig,
+    pub simulation: SimulationConfig,
+}
+
+impl GameConfig {
+    /// Checks for values the game can't run with, such as a tick rate of zero, which would make
+    /// every tick last forever.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.simulation.tick_rate == 0 {
+            return Err("simulation.tick_rate must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configs_without_ticks_are_rejected() {
+        let mut config = GameConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.simulation.tick_rate = 0;
+        assert!(config.validate().is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,205 @@
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+};
+
+use crate::{
+    config::{
+        AiLevel,
+        AiMode,
+    },
+    resources::{
+        Command,
+        CommandChannel,
+        Player,
+    },
+    simulation::{
+        BallBody,
+        Random,
+        Simulation,
+    },
+};
+
+use super::{
+    Controller,
+    ControllerContext,
+    movement_towards,
+    trajectory::predict_crossing,
+};
+
+/// The AiController controls the paddle of a single player. It sees the balls as they were the
+/// reaction time of the `Difficulty` ago, picks a target close to where it expects the most
+/// threatening ball in that view and moves the paddle towards it, as well as the `Difficulty`
+/// from the `GameConfig` allows. How the target is chosen depends on the `AiMode` of the
+/// difficulty. Balls served by the player are launched right away. The random numbers of the ai
+/// are derived from the seed of the match.
+pub struct AiController {
+    /// The player whose paddle is controlled.
+    player: Player,
+    random: Random,
+    /// The seed of the match `random` was seeded for.
+    seed: Option<u64>,
+    /// Seconds passed since the controller was created.
+    elapsed: f32,
+    /// The balls seen during the recent frames, together with the time they were seen at. The
+    /// first entry is the view the ai currently reacts to.
+    seen: VecDeque<(f32, Vec<BallBody>)>,
+    /// Whether the ball was coming closer in the view the ai reacted to last.
+    approaching: bool,
+    /// The distance by which the ai currently misses its target.
+    aim_error: f32,
+}
+
+impl AiController {
+    pub fn new(player: Player) -> Self {
+        Self {
+            player,
+            random: Random::new(0),
+            seed: None,
+            elapsed: 0.0,
+            seen: VecDeque::new(),
+            approaching: false,
+            aim_error: 0.0,
+        }
+    }
+}
+
+impl Controller for AiController {
+    fn update(&mut self, context: &ControllerContext, commands: &mut CommandChannel) {
+        let config = context.config;
+        let simulation = context.simulation;
+        let level = config.ai.level();
+
+        // start over with the random numbers of a new match, which differ between the players
+        if self.seed != Some(simulation.seed()) {
+            self.seed = Some(simulation.seed());
+            let player = match self.player {
+                Player::P1 => 1,
+                Player::P2 => 2,
+            };
+            self.random = Random::new(simulation.seed().wrapping_mul(31).wrapping_add(player));
+        }
+
+        // remember the current view, and forget the ones older than the reaction time except
+        // for the one to react to
+        self.elapsed += context.delta_seconds;
+        self.seen.push_back((self.elapsed, simulation.balls().to_vec()));
+        let reaction_start = self.elapsed - level.reaction_time;
+        while self.seen.len() > 1 && self.seen[1].0 <= reaction_start {
+            self.seen.pop_front();
+        }
+
+        // wait in the centre if there is no ball in play
+        let ball = most_threatening_ball(simulation, self.player, &self.seen[0].1);
+        let target = match ball {
+            Some(ball) => choose_target(simulation, self.player, ball, level),
+            None => simulation.arena_height() * 0.5,
+        };
+
+        // miss every ball coming closer by a new error
+        let approaching = ball
+            .map_or(false, |ball| time_to_contact(simulation, self.player, ball).is_some());
+        if approaching && !self.approaching {
+            self.aim_error = self.random.range(-level.aim_error, level.aim_error);
+        }
+        self.approaching = approaching;
+        let target = target + self.aim_error;
+
+        // move towards the target without overshooting it
+        let movement = movement_towards(context, self.player, target)
+            .max(-level.max_speed)
+            .min(level.max_speed);
+        commands.single_write(Command::MovePaddle(self.player, movement));
+
+        let player = self.player;
+        let serving = simulation.balls()
+            .iter()
+            .any(|ball| ball.serve.map_or(false, |serve| serve.player == player));
+        if serving {
+            commands.single_write(Command::LaunchBall(player));
+        }
+    }
+}
+
+/// Returns the y coordinate the paddle of the given player should move to, judging by the given
+/// ball.
+fn choose_target(simulation: &Simulation, player: Player, ball: &BallBody, level: &AiLevel) -> f32 {
+    let arena_height = simulation.arena_height();
+    let time = time_to_contact(simulation, player, ball);
+
+    match level.mode {
+        AiMode::Track => {
+            // anticipate where the ball reaches the paddle, if it is coming closer
+            if let Some(time) = time {
+                let reach_y = (ball.position[1] + ball.velocity[1] * time)
+                    .max(0.0)
+                    .min(arena_height);
+                ball.position[1] + (reach_y - ball.position[1]) * level.prediction
+            } else {
+                ball.position[1]
+            }
+        }
+        AiMode::Intercept => {
+            let crossing = if time.is_some() {
+                predict_crossing(ball, contact_x(simulation, player, ball), arena_height)
+            } else {
+                None
+            };
+
+            match crossing {
+                Some(crossing) => {
+                    ball.position[1] + (crossing - ball.position[1]) * level.prediction
+                }
+                // wait in the centre for the ball to come back
+                None => arena_height * 0.5,
+            }
+        }
+    }
+}
+
+/// Returns the ball of the given ones that threatens the goal of the given player the most: the
+/// approaching ball that reaches the paddle first or, if no ball is approaching, the ball closest
+/// to the paddle. Balls which were removed from play are ignored.
+fn most_threatening_ball<'a>(
+    simulation: &Simulation,
+    player: Player,
+    balls: &'a [BallBody],
+) -> Option<&'a BallBody> {
+    let balls = balls.iter().filter(|ball| !ball.removed);
+
+    let approaching = balls.clone()
+        .filter_map(|ball| time_to_contact(simulation, player, ball).map(|time| (time, ball)))
+        .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .map(|(_, ball)| ball);
+
+    approaching.or_else(|| {
+        let paddle_x = simulation.paddle(player).position[0];
+        balls.min_by(|a, b| {
+            let distance_a = (a.position[0] - paddle_x).abs();
+            let distance_b = (b.position[0] - paddle_x).abs();
+            distance_a.partial_cmp(&distance_b).unwrap_or(Ordering::Equal)
+        })
+    })
+}
+
+/// Returns the x coordinate of the centre of the ball when it touches the front of the paddle of
+/// the given player.
+fn contact_x(simulation: &Simulation, player: Player, ball: &BallBody) -> f32 {
+    let paddle = simulation.paddle(player);
+    paddle.position[0] + paddle.facing() * (paddle.width * 0.5 + ball.radius)
+}
+
+/// Returns the seconds until the ball reaches the front of the paddle of the given player, or
+/// `None` if it isn't coming closer.
+fn time_to_contact(simulation: &Simulation, player: Player, ball: &BallBody) -> Option<f32> {
+    let facing = simulation.paddle(player).facing();
+    let contact_x = contact_x(simulation, player, ball);
+    let approaching = ball.velocity[0] * facing < 0.0
+        && (ball.position[0] - contact_x) * facing > 0.0;
+
+    if approaching {
+        Some((contact_x - ball.position[0]) / ball.velocity[0])
+    } else {
+        None
+    }
+}
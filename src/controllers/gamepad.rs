@@ -0,0 +1,55 @@
+use crate::resources::{
+    Command,
+    CommandChannel,
+    GamepadButton,
+    Player,
+};
+
+use super::{
+    Controller,
+    ControllerContext,
+};
+
+/// The GamepadController converts the input of the gamepad assigned to a player into `Command`s,
+/// using the bindings from the `GameConfig`. It does nothing while no gamepad is assigned.
+pub struct GamepadController {
+    /// The player whose paddle is controlled.
+    player: Player,
+}
+
+impl GamepadController {
+    pub fn new(player: Player) -> Self {
+        Self {
+            player,
+        }
+    }
+}
+
+impl Controller for GamepadController {
+    fn update(&mut self, context: &ControllerContext, commands: &mut CommandChannel) {
+        let pad = match context.gamepads.assigned(self.player) {
+            Some(pad) => pad,
+            None => return,
+        };
+        let bindings = &context.config.gamepad;
+
+        let mut movement = pad.axis(bindings.movement_axis);
+        if movement.abs() < bindings.dead_zone {
+            movement = 0.0;
+        }
+        if bindings.invert_movement {
+            movement = -movement;
+        }
+        if pad.is_down(GamepadButton::DPadUp) {
+            movement += 1.0;
+        }
+        if pad.is_down(GamepadButton::DPadDown) {
+            movement -= 1.0;
+        }
+        commands.single_write(Command::MovePaddle(self.player, movement.max(-1.0).min(1.0)));
+
+        if pad.is_down(bindings.launch_button) {
+            commands.single_write(Command::LaunchBall(self.player));
+        }
+    }
+}
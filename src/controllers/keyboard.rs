@@ -0,0 +1,40 @@
+use crate::resources::{
+    Command,
+    CommandChannel,
+    KeyBindings,
+    Player,
+};
+
+use super::{
+    Controller,
+    ControllerContext,
+};
+
+/// The KeyboardController converts the input of a human player into `Command`s, using either the
+/// left or the right bindings of the input config.
+pub struct KeyboardController {
+    /// The player whose paddle is controlled.
+    player: Player,
+    bindings: KeyBindings,
+}
+
+impl KeyboardController {
+    pub fn new(player: Player, bindings: KeyBindings) -> Self {
+        Self {
+            player,
+            bindings,
+        }
+    }
+}
+
+impl Controller for KeyboardController {
+    fn update(&mut self, context: &ControllerContext, commands: &mut CommandChannel) {
+        let movement = context.input.axis_value(self.bindings.axis());
+        if let Some(movement) = movement {
+            commands.single_write(Command::MovePaddle(self.player, movement as f32));
+        }
+        if context.input.action_is_down(self.bindings.launch_action()).unwrap_or(false) {
+            commands.single_write(Command::LaunchBall(self.player));
+        }
+    }
+}
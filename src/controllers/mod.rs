@@ -0,0 +1,81 @@
+pub use self::{
+    ai::AiController,
+    gamepad::GamepadController,
+    keyboard::KeyboardController,
+    mouse::MouseController,
+    remote::RemoteController,
+    scripted::ScriptedController,
+};
+
+use amethyst::input::InputHandler;
+
+use crate::{
+    config::{
+        GameConfig,
+        PaddleMovement,
+    },
+    resources::{
+        CommandChannel,
+        ControllerKind,
+        Gamepads,
+        MouseCursor,
+        Player,
+        Script,
+    },
+    simulation::Simulation,
+};
+
+pub mod ai;
+pub mod gamepad;
+pub mod keyboard;
+pub mod mouse;
+pub mod remote;
+pub mod scripted;
+pub mod trajectory;
+
+/// A Controller decides what a single player does, e.g. based on the keyboard input or by looking
+/// at the `Simulation` like the ai. Controllers never touch the paddles themselves, they publish
+/// `Command`s via the `CommandChannel` instead, so all players obey the same rules no matter who
+/// or what controls them.
+pub trait Controller: Send + Sync {
+    /// Publishes the commands of the controlled player for the current frame.
+    fn update(&mut self, context: &ControllerContext, commands: &mut CommandChannel);
+}
+
+/// The ControllerContext holds everything a `Controller` may base its decisions on.
+pub struct ControllerContext<'a> {
+    pub input: &'a InputHandler<String, String>,
+    pub gamepads: &'a Gamepads,
+    pub cursor: &'a MouseCursor,
+    pub config: &'a GameConfig,
+    pub script: &'a Script,
+    pub simulation: &'a Simulation,
+    /// Seconds passed since the last frame.
+    pub delta_seconds: f32,
+}
+
+/// Creates a `Controller` of the given kind for the given player.
+pub fn create(kind: ControllerKind, player: Player) -> Box<dyn Controller> {
+    match kind {
+        ControllerKind::Keyboard(bindings) => Box::new(KeyboardController::new(player, bindings)),
+        ControllerKind::Gamepad => Box::new(GamepadController::new(player)),
+        ControllerKind::Mouse => Box::new(MouseController::new(player)),
+        ControllerKind::Ai => Box::new(AiController::new(player)),
+        ControllerKind::Remote => Box::new(RemoteController),
+        ControllerKind::Scripted => Box::new(ScriptedController::new(player)),
+    }
+}
+
+/// Returns the movement bringing the paddle of the given player to the target y coordinate as
+/// fast as possible, without overshooting it. The result may exceed the range of -1.0 to 1.0.
+pub fn movement_towards(context: &ControllerContext, player: Player, target: f32) -> f32 {
+    let paddle_config = &context.config.paddle;
+    let distance = target - context.simulation.paddle(player).position[1];
+
+    let mut speed = distance.abs() / context.simulation.tick_duration();
+    if paddle_config.movement == PaddleMovement::Momentum {
+        // the fastest speed from which the paddle can still stop at the target
+        speed = speed.min((2.0 * paddle_config.acceleration * distance.abs()).sqrt());
+    }
+    distance.signum() * speed / paddle_config.speed
+}
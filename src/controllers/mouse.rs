@@ -0,0 +1,42 @@
+use amethyst::renderer::MouseButton;
+
+use crate::resources::{
+    Command,
+    CommandChannel,
+    Player,
+};
+
+use super::{
+    Controller,
+    ControllerContext,
+    movement_towards,
+};
+
+/// The MouseController moves the paddle of a human player towards the height of the mouse
+/// cursor, as fast as the paddle speed allows. The left mouse button launches the ball.
+pub struct MouseController {
+    /// The player whose paddle is controlled.
+    player: Player,
+}
+
+impl MouseController {
+    pub fn new(player: Player) -> Self {
+        Self {
+            player,
+        }
+    }
+}
+
+impl Controller for MouseController {
+    fn update(&mut self, context: &ControllerContext, commands: &mut CommandChannel) {
+        // stop the paddle while the cursor is outside of the window
+        let movement = context.cursor.position.map_or(0.0, |[_, y]| {
+            movement_towards(context, self.player, y).max(-1.0).min(1.0)
+        });
+        commands.single_write(Command::MovePaddle(self.player, movement));
+
+        if context.input.mouse_button_is_down(MouseButton::Left) {
+            commands.single_write(Command::LaunchBall(self.player));
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use crate::resources::CommandChannel;
+
+use super::{
+    Controller,
+    ControllerContext,
+};
+
+/// The RemoteController stands in for a player on another instance. It doesn't publish any
+/// commands, the inputs of the player arrive over the network via the `NetSession` instead.
+pub struct RemoteController;
+
+impl Controller for RemoteController {
+    fn update(&mut self, _context: &ControllerContext, _commands: &mut CommandChannel) {}
+}
@@ -0,0 +1,54 @@
+use crate::resources::{
+    Command,
+    CommandChannel,
+    Player,
+};
+
+use super::{
+    Controller,
+    ControllerContext,
+};
+
+/// The ScriptedController publishes the commands the `Script` holds for its player, as soon as
+/// the `Simulation` reached the tick they are scheduled for. A movement lasts until the next one,
+/// like a key that is held down.
+pub struct ScriptedController {
+    /// The player whose paddle is controlled.
+    player: Player,
+    /// The first tick whose commands weren't published yet.
+    next_tick: u64,
+    /// The movement scheduled last.
+    movement: f32,
+}
+
+impl ScriptedController {
+    pub fn new(player: Player) -> Self {
+        Self {
+            player,
+            next_tick: 0,
+            movement: 0.0,
+        }
+    }
+}
+
+impl Controller for ScriptedController {
+    fn update(&mut self, context: &ControllerContext, commands: &mut CommandChannel) {
+        let tick = context.simulation.current_tick();
+
+        // start over once a new match started
+        if tick + 1 < self.next_tick {
+            self.next_tick = 0;
+            self.movement = 0.0;
+        }
+
+        for command in context.script.commands(self.player, self.next_tick, tick + 1) {
+            match *command {
+                Command::MovePaddle(_, movement) => self.movement = movement,
+                _ => commands.single_write(command.clone()),
+            }
+        }
+        self.next_tick = tick + 1;
+
+        commands.single_write(Command::MovePaddle(self.player, self.movement));
+    }
+}
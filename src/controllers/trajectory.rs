@@ -0,0 +1,26 @@
+use crate::simulation::BallBody;
+
+/// Predicts the y coordinate at which the centre of the ball crosses the given x coordinate,
+/// following its current velocity and its reflections off the top and bottom of the arena.
+/// Returns `None` if the ball is moving away from `x`, or not moving horizontally at all.
+pub fn predict_crossing(ball: &BallBody, x: f32, arena_height: f32) -> Option<f32> {
+    let time = (x - ball.position[0]) / ball.velocity[0];
+    if !time.is_finite() || time < 0.0 {
+        return None;
+    }
+
+    // The centre of the ball moves between `radius` and `arena_height - radius`. Unfolding the
+    // reflections, it keeps moving in a straight line, and the position inside the arena repeats
+    // every two heights of that range.
+    let range = arena_height - 2.0 * ball.radius;
+    if range <= 0.0 {
+        return Some(arena_height * 0.5);
+    }
+
+    let period = 2.0 * range;
+    let unfolded = ball.position[1] + ball.velocity[1] * time - ball.radius;
+    let folded = ((unfolded % period) + period) % period;
+    let y = if folded > range { period - folded } else { folded };
+
+    Some(y + ball.radius)
+}
@@ -0,0 +1,89 @@
+use gilrs::{
+    Axis,
+    Button,
+    EventType,
+    Gilrs,
+};
+
+use crate::resources::{
+    GamepadAxis,
+    GamepadButton,
+    GamepadEvent,
+};
+
+use super::GamepadSource;
+
+/// GilrsSource reports the gamepads of the operating system, using gilrs.
+pub struct GilrsSource {
+    gilrs: Gilrs,
+    /// Gamepads that were already connected on start, which gilrs doesn't report as events.
+    connected: Vec<GamepadEvent>,
+}
+
+impl GilrsSource {
+    pub fn new() -> Result<Self, gilrs::Error> {
+        let gilrs = Gilrs::new()?;
+        let connected = gilrs
+            .gamepads()
+            .map(|(id, _)| GamepadEvent::Connected(id.into()))
+            .collect();
+
+        Ok(Self {
+            gilrs,
+            connected,
+        })
+    }
+}
+
+impl GamepadSource for GilrsSource {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        events.append(&mut self.connected);
+
+        while let Some(event) = self.gilrs.next_event() {
+            let id = event.id.into();
+            let event = match event.event {
+                EventType::Connected => Some(GamepadEvent::Connected(id)),
+                EventType::Disconnected => Some(GamepadEvent::Disconnected(id)),
+                EventType::ButtonPressed(button, _) => {
+                    map_button(button).map(|button| GamepadEvent::ButtonPressed(id, button))
+                }
+                EventType::ButtonReleased(button, _) => {
+                    map_button(button).map(|button| GamepadEvent::ButtonReleased(id, button))
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    map_axis(axis).map(|axis| GamepadEvent::AxisChanged(id, axis, value))
+                }
+                _ => None,
+            };
+            events.extend(event);
+        }
+    }
+}
+
+fn map_button(button: Button) -> Option<GamepadButton> {
+    match button {
+        Button::South => Some(GamepadButton::South),
+        Button::East => Some(GamepadButton::East),
+        Button::North => Some(GamepadButton::North),
+        Button::West => Some(GamepadButton::West),
+        Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+        Button::RightTrigger => Some(GamepadButton::RightBumper),
+        Button::Select => Some(GamepadButton::Select),
+        Button::Start => Some(GamepadButton::Start),
+        Button::DPadUp => Some(GamepadButton::DPadUp),
+        Button::DPadDown => Some(GamepadButton::DPadDown),
+        Button::DPadLeft => Some(GamepadButton::DPadLeft),
+        Button::DPadRight => Some(GamepadButton::DPadRight),
+        _ => None,
+    }
+}
+
+fn map_axis(axis: Axis) -> Option<GamepadAxis> {
+    match axis {
+        Axis::LeftStickX => Some(GamepadAxis::LeftStickX),
+        Axis::LeftStickY => Some(GamepadAxis::LeftStickY),
+        Axis::RightStickX => Some(GamepadAxis::RightStickX),
+        Axis::RightStickY => Some(GamepadAxis::RightStickY),
+        _ => None,
+    }
+}
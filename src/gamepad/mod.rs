@@ -0,0 +1,39 @@
+#[cfg(feature = "gamepad")]
+pub use self::gilrs::GilrsSource;
+#[cfg(test)]
+pub use self::simulated::SimulatedGamepads;
+
+use crate::resources::GamepadEvent;
+
+#[cfg(feature = "gamepad")]
+pub mod gilrs;
+#[cfg(test)]
+pub mod simulated;
+
+/// A GamepadSource reports the connected gamepads and their input as `GamepadEvent`s.
+pub trait GamepadSource {
+    /// Appends all events since the last call to `events`.
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>);
+}
+
+/// Returns the gamepads of the operating system, if the `gamepad` feature is enabled and they
+/// are available. Otherwise a source without any gamepads is returned.
+pub fn default_source() -> Box<dyn GamepadSource> {
+    #[cfg(feature = "gamepad")]
+    {
+        match GilrsSource::new() {
+            Ok(source) => return Box::new(source),
+            Err(error) => warn!("Gamepads are not available: {}", error),
+        }
+    }
+
+    Box::new(NoGamepads)
+}
+
+/// NoGamepads is the `GamepadSource` used when no gamepads are available, it never reports any
+/// events.
+struct NoGamepads;
+
+impl GamepadSource for NoGamepads {
+    fn poll(&mut self, _events: &mut Vec<GamepadEvent>) {}
+}
@@ -0,0 +1,56 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use crate::resources::GamepadEvent;
+
+use super::GamepadSource;
+
+/// SimulatedGamepads is a `GamepadSource` that reports the events pushed into it by tests, so
+/// they can drive the gamepad handling without any hardware. Clones share the same queue, so one
+/// clone can be handed to the `GamepadInputSystem` while another one is used to push events.
+#[derive(Clone, Default)]
+pub struct SimulatedGamepads {
+    events: Arc<Mutex<VecDeque<GamepadEvent>>>,
+}
+
+impl SimulatedGamepads {
+    /// Queues an event, which is reported by the next `poll`.
+    pub fn push(&self, event: GamepadEvent) {
+        self.events.lock().expect("Gamepad event queue poisoned").push_back(event);
+    }
+}
+
+impl GamepadSource for SimulatedGamepads {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        events.extend(self.events.lock().expect("Gamepad event queue poisoned").drain(..));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushed_events_are_polled_once_in_order() {
+        let mut source = SimulatedGamepads::default();
+        let pusher = source.clone();
+        pusher.push(GamepadEvent::Connected(0));
+        pusher.push(GamepadEvent::Disconnected(0));
+
+        let mut events = Vec::new();
+        source.poll(&mut events);
+        match events.as_slice() {
+            [GamepadEvent::Connected(0), GamepadEvent::Disconnected(0)] => {}
+            _ => panic!("unexpected events {:?}", events),
+        }
+
+        events.clear();
+        source.poll(&mut events);
+        assert!(events.is_empty());
+    }
+}
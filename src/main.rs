@@ -20,7 +20,13 @@ use amethyst::{
     utils::application_root_dir,
 };
 
-use config::GameConfig;
+use config::{
+    GameConfig,
+    INPUT_CONFIG_PATH,
+    USER_INPUT_CONFIG_PATH,
+};
+use replay::Replay;
+use resources::Script;
 use states::{
     game::GamePrefabData,
     LoadingState,
@@ -28,7 +34,12 @@ use states::{
 
 mod components;
 mod config;
+mod controllers;
+mod gamepad;
+mod net;
+mod replay;
 mod resources;
+mod simulation;
 mod states;
 mod systems;
 
@@ -44,9 +55,15 @@ fn main() -> amethyst::Result<()> {
     // game config
     let config_path = app_root.join("resources/config.ron");
     let config = GameConfig::load(&config_path);
+    config.validate().map_err(amethyst::Error::from_string)?;
 
-    // key bindings
-    let key_bindings_path = app_root.join("resources/input.ron");
+    // key bindings, preferring the ones changed by the user
+    let user_key_bindings_path = app_root.join(USER_INPUT_CONFIG_PATH);
+    let key_bindings_path = if user_key_bindings_path.exists() {
+        user_key_bindings_path
+    } else {
+        app_root.join(INPUT_CONFIG_PATH)
+    };
 
     let pipe = Pipeline::build()
         .with_stage(
@@ -56,23 +73,59 @@ fn main() -> amethyst::Result<()> {
                 .with_pass(DrawUi::new()),
         );
 
+    // gamepads of the operating system
+    let gamepad_source = gamepad::default_source();
+
     let game_data = GameDataBuilder::default()
         .with_bundle(RenderBundle::new(pipe, Some(display_config)).with_sprite_sheet_processor())?
         .with_bundle(TransformBundle::new())?
         .with_bundle(InputBundle::<String, String>::new().with_bindings_from_file(key_bindings_path)?)?
         .with_bundle(UiBundle::<String, String>::new())?
         .with_bundle(systems::CoreSystemsBundle)?
-        .with(PrefabLoaderSystem::<GamePrefabData>::default(), "", &[]);
+        .with(PrefabLoaderSystem::<GamePrefabData>::default(), "", &[])
+        .with_thread_local(systems::GamepadInputSystem::new(gamepad_source));
+
+    // let a script play the players it holds commands for, if one was passed via
+    // `--script <path>`
+    let script = match argument("--script") {
+        Some(path) => Script::load(&path).unwrap_or_else(|error| {
+            error!("Failed to load script {}: {}", path, error);
+            Script::default()
+        }),
+        None => Script::default(),
+    };
+
+    // play a replay back instead of showing the menu, if one was passed via `--replay <path>`
+    let loading_state = match argument("--replay") {
+        Some(path) => match Replay::load(&path) {
+            Ok(replay) => LoadingState::with_replay(replay),
+            Err(error) => {
+                error!("Failed to load replay {}: {}", path, error);
+                LoadingState::default()
+            }
+        },
+        None => LoadingState::default(),
+    };
 
     let assets_dir = app_root.join("assets");
 
-    let mut game = Application::build(assets_dir, LoadingState::default())?
-        .with_resource(config.arena)
-        .with_resource(config.ball)
-        .with_resource(config.paddle)
+    let mut game = Application::build(assets_dir, loading_state)?
+        .with_resource(config)
+        .with_resource(script)
         .build(game_data)?;
 
     game.run();
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Returns the value following the argument with the given name, if any.
+fn argument(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+    }
+    None
+}
@@ -0,0 +1,108 @@
+use crate::{
+    config::GameConfig,
+    resources::Player,
+};
+
+use super::{
+    Message,
+    Transport,
+};
+
+/// Seconds between two `Join` messages, as they may get lost.
+const JOIN_INTERVAL: f32 = 0.25;
+
+/// A Connection holds everything a match over the network is started with, once both players
+/// found each other.
+pub struct Connection {
+    pub transport: Box<dyn Transport>,
+    /// The player controlled by this instance, the host plays on the left.
+    pub local: Player,
+    pub seed: u64,
+    /// The config of the host, both `Simulation`s are created with.
+    pub config: GameConfig,
+    /// The `Start` message of the host, which is repeated if the joining player didn't get it.
+    pub start: Option<Message>,
+}
+
+/// The Lobby brings the host and the joining player together. The joining player keeps sending
+/// `Join` messages, until the host replies with the seed and the `GameConfig` of the match.
+pub struct Lobby {
+    transport: Option<Box<dyn Transport>>,
+    /// The `Start` message of the host, `None` for the joining player.
+    start: Option<(Message, u64, GameConfig)>,
+    /// Seconds until the next `Join` message is sent.
+    join_timer: f32,
+}
+
+impl Lobby {
+    /// Waits for a player to join a match with the given config and seed.
+    pub fn host(
+        transport: Box<dyn Transport>,
+        config: GameConfig,
+        seed: u64,
+    ) -> Result<Self, String> {
+        let serialized = ron::ser::to_string(&config).map_err(|error| error.to_string())?;
+        Ok(Self {
+            transport: Some(transport),
+            start: Some((Message::Start { seed, config: serialized }, seed, config)),
+            join_timer: 0.0,
+        })
+    }
+
+    /// Joins the host the given transport is connected to.
+    pub fn join(transport: Box<dyn Transport>) -> Self {
+        Self {
+            transport: Some(transport),
+            start: None,
+            join_timer: 0.0,
+        }
+    }
+
+    /// Handles the received messages and returns the `Connection` once the match can start.
+    pub fn update(&mut self, dt: f32) -> Result<Option<Connection>, String> {
+        let mut transport = match self.transport.take() {
+            Some(transport) => transport,
+            None => return Ok(None),
+        };
+
+        if self.start.is_none() {
+            self.join_timer -= dt;
+            if self.join_timer <= 0.0 {
+                self.join_timer = JOIN_INTERVAL;
+                transport.send(&Message::Join);
+            }
+        }
+
+        while let Some(message) = transport.receive() {
+            match (message, self.start.take()) {
+                // the host starts as soon as a player joined
+                (Message::Join, Some((start, seed, config))) => {
+                    transport.send(&start);
+                    return Ok(Some(Connection {
+                        transport,
+                        local: Player::P1,
+                        seed,
+                        config,
+                        start: Some(start),
+                    }));
+                }
+                (Message::Start { seed, config }, None) => {
+                    let config: GameConfig =
+                        ron::de::from_str(&config).map_err(|error| error.to_string())?;
+                    config.validate()?;
+                    return Ok(Some(Connection {
+                        transport,
+                        local: Player::P2,
+                        seed,
+                        config,
+                        start: None,
+                    }));
+                }
+                (_, start) => self.start = start,
+            }
+        }
+
+        self.transport = Some(transport);
+        Ok(None)
+    }
+}
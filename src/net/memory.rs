@@ -0,0 +1,54 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use super::{
+    Message,
+    Transport,
+};
+
+type Queue = Arc<Mutex<VecDeque<Vec<u8>>>>;
+
+/// The MemoryTransport delivers messages to the other transport of its pair within the same
+/// process, e.g. to test matches without any sockets. Messages are encoded on the way, like they
+/// are by the `UdpTransport`.
+pub struct MemoryTransport {
+    outgoing: Queue,
+    incoming: Queue,
+}
+
+impl MemoryTransport {
+    /// Create two transports connected to each other.
+    pub fn pair() -> (Self, Self) {
+        let (first, second) = (Queue::default(), Queue::default());
+        let left = Self {
+            outgoing: first.clone(),
+            incoming: second.clone(),
+        };
+        let right = Self {
+            outgoing: second,
+            incoming: first,
+        };
+        (left, right)
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&mut self, message: &Message) {
+        self.outgoing.lock().expect("Message queue poisoned").push_back(message.encode());
+    }
+
+    fn receive(&mut self) -> Option<Message> {
+        let mut incoming = self.incoming.lock().expect("Message queue poisoned");
+        while let Some(bytes) = incoming.pop_front() {
+            if let Some(message) = Message::decode(&bytes) {
+                return Some(message);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+pub use self::memory::MemoryTransport;
+pub use self::{
+    lobby::{
+        Connection,
+        Lobby,
+    },
+    protocol::{
+        Checksum,
+        Message,
+        NetInput,
+    },
+    rollback::RollbackSession,
+    session::NetSession,
+    transport::{
+        with_conditions,
+        LossyTransport,
+        Transport,
+        UdpTransport,
+    },
+};
+
+pub mod lobby;
+#[cfg(test)]
+pub mod memory;
+pub mod protocol;
+pub mod rollback;
+pub mod session;
+pub mod transport;
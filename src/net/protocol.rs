@@ -0,0 +1,258 @@
+use crate::{
+    resources::Player,
+    simulation::Inputs,
+};
+
+/// Marks the packets of this game, anything else received on the socket is ignored.
+const MAGIC: &[u8; 4] = b"PONG";
+
+/// Version of the protocol. Instances speaking another version can't play together.
+const PROTOCOL_VERSION: u8 = 2;
+
+const JOIN: u8 = 0;
+const START: u8 = 1;
+const INPUTS: u8 = 2;
+const LEAVE: u8 = 3;
+
+/// NetInput is what a player did during a single tick, as exchanged over the network. The
+/// movement is quantized, so both instances apply exactly the same value.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NetInput {
+    /// The movement between -127 (down) and 127 (up).
+    pub movement: i8,
+    pub launch: bool,
+}
+
+impl NetInput {
+    /// Takes the inputs of the given player.
+    pub fn from_inputs(inputs: &Inputs, player: Player) -> Self {
+        let movement = inputs.movement(player).max(-1.0).min(1.0);
+        Self {
+            movement: (movement * 127.0).round() as i8,
+            launch: inputs.launch(player),
+        }
+    }
+
+    /// Applies the input to the inputs of the given player.
+    pub fn apply(self, inputs: &mut Inputs, player: Player) {
+        inputs.set_movement(player, f32::from(self.movement) / 127.0);
+        if self.launch {
+            inputs.set_launch(player);
+        }
+    }
+}
+
+/// A Checksum of the confirmed `Simulation` of the sender at the given tick, to detect that the
+/// instances of a match went out of sync.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Checksum {
+    pub tick: u64,
+    pub value: u64,
+}
+
+/// Message enumeration describes everything the two instances of a match over the network tell
+/// each other.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Sent by the joining player until the match starts.
+    Join,
+    /// Sent by the host in reply to `Join`, with the seed and the `GameConfig`, serialized as
+    /// ron, to create the same `Simulation` with.
+    Start { seed: u64, config: String },
+    /// The inputs of the sender, starting at the given tick, the number of inputs of the
+    /// receiver the sender got so far and the latest checksum of the sender, if any.
+    Inputs { ack: u64, start: u64, checksum: Option<Checksum>, inputs: Vec<NetInput> },
+    /// Sent when leaving the match.
+    Leave,
+}
+
+impl Message {
+    /// Encodes the message into the bytes of a packet.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(PROTOCOL_VERSION);
+
+        match self {
+            Message::Join => bytes.push(JOIN),
+            Message::Start { seed, config } => {
+                bytes.push(START);
+                bytes.extend_from_slice(&seed.to_le_bytes());
+                bytes.extend_from_slice(config.as_bytes());
+            }
+            Message::Inputs { ack, start, checksum, inputs } => {
+                bytes.push(INPUTS);
+                bytes.extend_from_slice(&ack.to_le_bytes());
+                bytes.extend_from_slice(&start.to_le_bytes());
+                match checksum {
+                    Some(checksum) => {
+                        bytes.push(1);
+                        bytes.extend_from_slice(&checksum.tick.to_le_bytes());
+                        bytes.extend_from_slice(&checksum.value.to_le_bytes());
+                    }
+                    None => bytes.push(0),
+                }
+                for input in inputs {
+                    bytes.push(input.movement as u8);
+                    bytes.push(input.launch as u8);
+                }
+            }
+            Message::Leave => bytes.push(LEAVE),
+        }
+        bytes
+    }
+
+    /// Decodes the bytes of a packet, returning `None` for anything that isn't a valid message.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let header = MAGIC.len() + 2;
+        if bytes.len() < header || &bytes[..MAGIC.len()] != MAGIC {
+            return None;
+        }
+        if bytes[MAGIC.len()] != PROTOCOL_VERSION {
+            return None;
+        }
+
+        let body = &bytes[header..];
+        match bytes[header - 1] {
+            JOIN => Some(Message::Join),
+            START => {
+                let seed = read_u64(body, 0)?;
+                let config = String::from_utf8(body[8..].to_vec()).ok()?;
+                Some(Message::Start { seed, config })
+            }
+            INPUTS => {
+                let ack = read_u64(body, 0)?;
+                let start = read_u64(body, 8)?;
+                let (checksum, inputs) = match body.get(16)? {
+                    0 => (None, &body[17..]),
+                    1 => {
+                        let tick = read_u64(body, 17)?;
+                        let value = read_u64(body, 25)?;
+                        (Some(Checksum { tick, value }), &body[33..])
+                    }
+                    _ => return None,
+                };
+                if inputs.len() % 2 != 0 {
+                    return None;
+                }
+                let inputs = inputs
+                    .chunks(2)
+                    .map(|input| NetInput {
+                        movement: input[0] as i8,
+                        launch: input[1] != 0,
+                    })
+                    .collect();
+                Some(Message::Inputs { ack, start, checksum, inputs })
+            }
+            LEAVE => Some(Message::Leave),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a little endian u64 at the given offset.
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let bytes = bytes.get(offset..offset + 8)?;
+    let mut value = [0; 8];
+    value.copy_from_slice(bytes);
+    Some(u64::from_le_bytes(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs_message(checksum: Option<Checksum>) -> Message {
+        Message::Inputs {
+            ack: 1234,
+            start: 1200,
+            checksum,
+            inputs: vec![
+                NetInput { movement: -127, launch: false },
+                NetInput { movement: 127, launch: true },
+                NetInput { movement: 0, launch: false },
+            ],
+        }
+    }
+
+    #[test]
+    fn messages_survive_encoding() {
+        let messages = vec![
+            Message::Join,
+            Message::Start { seed: u64::max_value(), config: "(ball: (radius: 2.5))".to_string() },
+            Message::Start { seed: 0, config: String::new() },
+            inputs_message(None),
+            inputs_message(Some(Checksum { tick: 600, value: 0xdead_beef_0123_4567 })),
+            Message::Inputs { ack: 0, start: 0, checksum: None, inputs: Vec::new() },
+            Message::Leave,
+        ];
+        for message in messages {
+            assert_eq!(Message::decode(&message.encode()), Some(message));
+        }
+    }
+
+    #[test]
+    fn truncated_packets_are_rejected() {
+        let bytes = inputs_message(Some(Checksum { tick: 60, value: 42 })).encode();
+
+        // everything up to the first input is required
+        let header = MAGIC.len() + 2 + 8 + 8 + 1 + 8 + 8;
+        for length in 0..header {
+            assert_eq!(Message::decode(&bytes[..length]), None, "length {}", length);
+        }
+        assert!(Message::decode(&bytes[..header]).is_some());
+
+        // an input is cut in half
+        assert_eq!(Message::decode(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(Message::decode(&bytes[..header + 1]), None);
+
+        let bytes = Message::Start { seed: 7, config: String::new() }.encode();
+        assert_eq!(Message::decode(&bytes[..bytes.len() - 1]), None);
+    }
+
+    #[test]
+    fn foreign_packets_are_rejected() {
+        let bytes = inputs_message(None).encode();
+
+        let mut other = bytes.clone();
+        other[0] = b'X';
+        assert_eq!(Message::decode(&other), None);
+
+        let mut other = bytes.clone();
+        other[MAGIC.len()] = PROTOCOL_VERSION + 1;
+        assert_eq!(Message::decode(&other), None);
+
+        let mut other = bytes.clone();
+        other[MAGIC.len() + 1] = 42;
+        assert_eq!(Message::decode(&other), None);
+
+        // the checksum is either there or not
+        let mut other = bytes.clone();
+        other[MAGIC.len() + 2 + 16] = 2;
+        assert_eq!(Message::decode(&other), None);
+
+        let mut other = Message::Start { seed: 7, config: String::new() }.encode();
+        other.extend_from_slice(&[0xff, 0xfe]);
+        assert_eq!(Message::decode(&other), None);
+    }
+
+    #[test]
+    fn inputs_are_quantized() {
+        let mut inputs = Inputs::default();
+        inputs.set_movement(Player::P1, 0.5);
+        inputs.set_movement(Player::P2, -3.0);
+        inputs.set_launch(Player::P2);
+
+        let p1 = NetInput::from_inputs(&inputs, Player::P1);
+        let p2 = NetInput::from_inputs(&inputs, Player::P2);
+        assert_eq!(p1, NetInput { movement: 64, launch: false });
+        assert_eq!(p2, NetInput { movement: -127, launch: true });
+
+        let mut applied = Inputs::default();
+        p1.apply(&mut applied, Player::P1);
+        p2.apply(&mut applied, Player::P2);
+        assert_eq!(applied.movement(Player::P1), 64.0 / 127.0);
+        assert_eq!(applied.movement(Player::P2), -1.0);
+        assert!(!applied.launch(Player::P1));
+        assert!(applied.launch(Player::P2));
+    }
+}
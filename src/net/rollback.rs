@@ -0,0 +1,337 @@
+use std::{
+    collections::BTreeMap,
+    mem,
+};
+
+use crate::{
+    resources::{
+        GameEvent,
+        Player,
+    },
+    simulation::{
+        Inputs,
+        Simulation,
+    },
+};
+
+use super::{
+    Checksum,
+    NetInput,
+};
+
+/// The maximum amount of time a single frame may simulate, like the `Simulation` does.
+const MAX_FRAME_DURATION: f32 = 0.25;
+
+/// Number of confirmed ticks between two checksums.
+const CHECKSUM_INTERVAL: u64 = 60;
+
+/// The RollbackSession keeps the `Simulation` of both instances of a match over the network in
+/// sync, without waiting for the inputs of the remote player every tick.
+///
+/// The local inputs are applied a few ticks later than they happen, which gives them time to
+/// reach the remote player. Ticks whose remote inputs haven't arrived yet are simulated with a
+/// prediction instead, namely the last movement of the remote player. Once the actual inputs
+/// arrive, the predicted ticks are rolled back and simulated again from the last confirmed state,
+/// which was simulated with the actual inputs of both players only. Both instances therefore
+/// confirm exactly the same ticks, as long as the `Simulation` is deterministic. To notice if
+/// they don't, a checksum of the confirmed state is taken every `CHECKSUM_INTERVAL` ticks and
+/// compared with the one of the remote player.
+pub struct RollbackSession {
+    local: Player,
+    input_delay: u64,
+    max_prediction: u64,
+    /// The `Simulation` after the ticks both inputs are known for.
+    confirmed: Simulation,
+    /// The inputs of the local player, indexed by tick.
+    local_inputs: Vec<NetInput>,
+    /// The inputs of the remote player received so far, indexed by tick.
+    remote_inputs: Vec<NetInput>,
+    /// Time that was passed to `advance`, but not yet simulated.
+    accumulator: f32,
+    /// The checksums of the confirmed `Simulation`, one every `CHECKSUM_INTERVAL` ticks.
+    checksums: Vec<u64>,
+    /// The checksums of the remote player for ticks that weren't confirmed locally yet, by tick.
+    remote_checksums: BTreeMap<u64, u64>,
+    /// The first tick whose checksums differed, if any.
+    desync: Option<u64>,
+}
+
+impl RollbackSession {
+    /// Create a `RollbackSession` for the given `Simulation`, which hasn't been advanced yet.
+    pub fn new(
+        simulation: &Simulation,
+        local: Player,
+        input_delay: u64,
+        max_prediction: u64,
+    ) -> Self {
+        Self {
+            local,
+            input_delay,
+            max_prediction,
+            confirmed: simulation.clone(),
+            // nothing happens during the ticks the first local input is delayed by
+            local_inputs: vec![NetInput::default(); input_delay as usize],
+            remote_inputs: Vec::new(),
+            accumulator: 0.0,
+            checksums: Vec::new(),
+            remote_checksums: BTreeMap::new(),
+            desync: None,
+        }
+    }
+
+    pub fn local_player(&self) -> Player {
+        self.local
+    }
+
+    /// Returns the `Simulation` after the ticks both inputs are known for.
+    pub fn confirmed(&self) -> &Simulation {
+        &self.confirmed
+    }
+
+    /// Returns the inputs of the local player, indexed by tick.
+    pub fn local_inputs(&self) -> &[NetInput] {
+        &self.local_inputs
+    }
+
+    /// Returns the number of consecutive remote inputs received so far.
+    pub fn received(&self) -> u64 {
+        self.remote_inputs.len() as u64
+    }
+
+    /// Returns the latest checksum of the confirmed `Simulation`, if any.
+    pub fn checksum(&self) -> Option<Checksum> {
+        self.checksums.last().map(|&value| Checksum {
+            tick: self.checksums.len() as u64 * CHECKSUM_INTERVAL,
+            value,
+        })
+    }
+
+    /// Returns the first tick at which the confirmed `Simulation` differed from the one of the
+    /// remote player, if it did.
+    pub fn desync(&self) -> Option<u64> {
+        self.desync
+    }
+
+    /// Adds a checksum of the remote player, which is compared once the tick was confirmed
+    /// locally as well.
+    pub fn add_remote_checksum(&mut self, checksum: Checksum) {
+        if checksum.tick > 0 && checksum.tick % CHECKSUM_INTERVAL == 0 {
+            self.remote_checksums.insert(checksum.tick, checksum.value);
+            self.compare_checksums();
+        }
+    }
+
+    /// Adds the remote inputs starting at the given tick. Inputs that were received before are
+    /// skipped, inputs following a gap are dropped, as they are sent again.
+    pub fn add_remote_inputs(&mut self, start: u64, inputs: &[NetInput]) {
+        let received = self.received();
+        if start <= received {
+            let skip = (received - start) as usize;
+            self.remote_inputs.extend(inputs.iter().skip(skip).cloned());
+        }
+    }
+
+    /// Advances the `Simulation` by `dt` seconds, applying the given local input to each of the
+    /// ticks. The `Simulation` isn't advanced further than `max_prediction` ticks beyond the
+    /// received remote inputs, it waits for them instead. Returns the `GameEvent`s of the ticks
+    /// that were confirmed, since the ones of predicted ticks may not actually happen.
+    pub fn advance(
+        &mut self,
+        dt: f32,
+        input: NetInput,
+        simulation: &mut Simulation,
+    ) -> Vec<GameEvent> {
+        self.accumulator += dt.min(MAX_FRAME_DURATION);
+        let ticks = (self.accumulator / simulation.tick_duration()) as u64;
+        self.accumulator -= ticks as f32 * simulation.tick_duration();
+
+        // wait for the remote player instead of predicting too far
+        let current = simulation.current_tick();
+        let limit = self.received() + self.max_prediction;
+        let target = (current + ticks).min(limit.max(current));
+        if target < current + ticks {
+            self.accumulator = 0.0;
+        }
+
+        // the local input of this frame is applied after the input delay
+        while (self.local_inputs.len() as u64) < target + self.input_delay {
+            self.local_inputs.push(input);
+        }
+
+        // confirm the ticks both inputs are known for
+        let mut events = Vec::new();
+        let confirmable = self.received().min(self.local_inputs.len() as u64).min(target);
+        for tick in self.confirmed.current_tick()..confirmable {
+            let inputs = self.inputs(tick);
+            self.confirmed.tick(&inputs);
+            events.extend(self.confirmed.events().iter().cloned());
+
+            let current = self.confirmed.current_tick();
+            let checksummed = self.checksums.len() as u64 * CHECKSUM_INTERVAL;
+            if current % CHECKSUM_INTERVAL == 0 && current > checksummed {
+                self.checksums.push(checksum(&self.confirmed));
+            }
+        }
+        self.compare_checksums();
+
+        // simulate the remaining ticks again from the confirmed state, with the predictions
+        // corrected by the remote inputs received in the meantime
+        *simulation = self.confirmed.clone();
+        for tick in simulation.current_tick()..target {
+            let inputs = self.inputs(tick);
+            simulation.tick(&inputs);
+        }
+
+        events
+    }
+
+    /// Compares the remote checksums of the ticks that were confirmed locally, and forgets them.
+    fn compare_checksums(&mut self) {
+        let confirmed = self.checksums.len() as u64 * CHECKSUM_INTERVAL;
+        let pending = self.remote_checksums.split_off(&(confirmed + 1));
+        for (tick, remote) in mem::replace(&mut self.remote_checksums, pending) {
+            let local = self.checksums[(tick / CHECKSUM_INTERVAL - 1) as usize];
+            if local != remote && self.desync.is_none() {
+                self.desync = Some(tick);
+            }
+        }
+    }
+
+    /// Returns the inputs of both players for the given tick, predicting the remote input if it
+    /// wasn't received yet.
+    fn inputs(&self, tick: u64) -> Inputs {
+        let mut inputs = Inputs::default();
+        let local = self.local_inputs.get(tick as usize).cloned().unwrap_or_default();
+        local.apply(&mut inputs, self.local);
+
+        let remote = match self.remote_inputs.get(tick as usize) {
+            Some(&input) => input,
+            // the remote player most likely keeps moving, but doesn't launch the ball again
+            None => NetInput {
+                launch: false,
+                ..self.remote_inputs.last().cloned().unwrap_or_default()
+            },
+        };
+        remote.apply(&mut inputs, self.local.opponent());
+        inputs
+    }
+}
+
+/// Returns a checksum of the tick, the balls, the paddles and the score of the `Simulation`,
+/// which is the same on every platform.
+fn checksum(simulation: &Simulation) -> u64 {
+    // 64 bit FNV-1a
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut add = |value: u64| {
+        for byte in value.to_le_bytes().iter() {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+
+    add(simulation.current_tick());
+    for ball in simulation.balls() {
+        let values = [ball.position[0], ball.position[1], ball.velocity[0], ball.velocity[1]];
+        values.iter().for_each(|value| add(u64::from(value.to_bits())));
+    }
+    for &player in [Player::P1, Player::P2].iter() {
+        let position = simulation.paddle(player).position;
+        position.iter().for_each(|value| add(u64::from(value.to_bits())));
+    }
+    let score = simulation.score();
+    let values = [score.score_left, score.score_right, score.games_left, score.games_right];
+    values.iter().for_each(|&value| add(u64::from(value as u32)));
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::GameConfig;
+
+    use super::*;
+
+    /// Plays the given number of frames of 1/60 s with two sessions, which hand their inputs and
+    /// checksums directly to each other.
+    fn play(sessions: &mut [(RollbackSession, Simulation); 2], frames: usize) {
+        for frame in 0..frames {
+            for (index, (session, simulation)) in sessions.iter_mut().enumerate() {
+                let input = NetInput {
+                    movement: if (frame / 50 + index) % 2 == 0 { 127 } else { -90 },
+                    launch: frame % 97 == index,
+                };
+                session.advance(1.0 / 60.0, input, simulation);
+            }
+
+            for index in 0..2 {
+                let inputs = sessions[index].0.local_inputs().to_vec();
+                let checksum = sessions[index].0.checksum();
+                let remote = &mut sessions[1 - index].0;
+                remote.add_remote_inputs(0, &inputs);
+                if let Some(checksum) = checksum {
+                    remote.add_remote_checksum(checksum);
+                }
+            }
+        }
+    }
+
+    /// Creates the sessions of both players, whose simulations use the given configs.
+    fn sessions(
+        config: &GameConfig,
+        remote_config: &GameConfig,
+    ) -> [(RollbackSession, Simulation); 2] {
+        let host = Simulation::new(config, 1);
+        let remote = Simulation::new(remote_config, 1);
+        [
+            (RollbackSession::new(&host, Player::P1, 2, 8), host),
+            (RollbackSession::new(&remote, Player::P2, 3, 8), remote),
+        ]
+    }
+
+    #[test]
+    fn identical_matches_stay_in_sync() {
+        let config = GameConfig::default();
+        let mut sessions = sessions(&config, &config);
+        play(&mut sessions, 600);
+
+        let (host, remote) = (&sessions[0].0, &sessions[1].0);
+        assert!(host.confirmed().current_tick() >= 590);
+        assert_eq!(host.checksum(), remote.checksum());
+        assert_eq!(host.checksum().map(|checksum| checksum.tick % CHECKSUM_INTERVAL), Some(0));
+        assert_eq!((host.desync(), remote.desync()), (None, None));
+    }
+
+    #[test]
+    fn diverging_matches_are_detected() {
+        let config = GameConfig::default();
+        let mut remote_config = config.clone();
+        remote_config.paddle.speed *= 1.01;
+        let mut sessions = sessions(&config, &remote_config);
+        play(&mut sessions, 200);
+
+        // both notice at the first checksum
+        assert_eq!(sessions[0].0.desync(), Some(CHECKSUM_INTERVAL));
+        assert_eq!(sessions[1].0.desync(), Some(CHECKSUM_INTERVAL));
+    }
+
+    #[test]
+    fn remote_checksums_are_compared_once_confirmed() {
+        let config = GameConfig::default();
+        let mut sessions = sessions(&config, &config);
+        play(&mut sessions, 100);
+
+        // a checksum for a tick that isn't confirmed yet is kept until it is
+        let session = &mut sessions[0].0;
+        let tick = (session.confirmed().current_tick() / CHECKSUM_INTERVAL + 2) * CHECKSUM_INTERVAL;
+        session.add_remote_checksum(Checksum { tick, value: 0 });
+        assert_eq!(session.desync(), None);
+
+        // checksums of other ticks are ignored
+        session.add_remote_checksum(Checksum { tick: 1, value: 0 });
+        session.add_remote_checksum(Checksum { tick: 0, value: 0 });
+        assert_eq!(session.desync(), None);
+
+        play(&mut sessions, 200);
+        assert_eq!(sessions[0].0.desync(), Some(tick));
+        assert_eq!(sessions[1].0.desync(), None);
+    }
+}
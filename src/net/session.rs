@@ -0,0 +1,247 @@
+use crate::{
+    resources::{
+        GameEvent,
+        Player,
+    },
+    simulation::Simulation,
+};
+
+use super::{
+    Connection,
+    Message,
+    NetInput,
+    RollbackSession,
+    Transport,
+};
+
+/// Seconds without any message of the remote player, after which the connection counts as lost.
+const TIMEOUT: f32 = 5.0;
+
+/// Maximum number of inputs sent with a single message.
+const MAX_INPUTS_PER_MESSAGE: usize = 256;
+
+/// The NetSession runs a match over the network. Every frame it receives the messages of the
+/// remote player, advances the `RollbackSession` and sends all local inputs the remote player
+/// didn't acknowledge yet, so lost messages are made up for by the following ones. Each message
+/// carries the latest checksum of the confirmed `Simulation` as well, to notice if both players
+/// no longer play the same match.
+pub struct NetSession {
+    transport: Box<dyn Transport>,
+    rollback: RollbackSession,
+    /// Number of local inputs the remote player acknowledged.
+    acknowledged: u64,
+    /// The `Start` message of the host, which is repeated if the joining player didn't get it.
+    start: Option<Message>,
+    /// Seconds since the last message of the remote player.
+    silence: f32,
+    disconnected: bool,
+}
+
+impl NetSession {
+    pub fn new(
+        connection: Connection,
+        simulation: &Simulation,
+        input_delay: u64,
+        max_prediction: u64,
+    ) -> Self {
+        Self {
+            transport: connection.transport,
+            rollback: RollbackSession::new(
+                simulation,
+                connection.local,
+                input_delay,
+                max_prediction,
+            ),
+            acknowledged: 0,
+            start: connection.start,
+            silence: 0.0,
+            disconnected: false,
+        }
+    }
+
+    pub fn local_player(&self) -> Player {
+        self.rollback.local_player()
+    }
+
+    /// Returns the `Simulation` after the ticks both inputs are known for.
+    pub fn confirmed(&self) -> &Simulation {
+        self.rollback.confirmed()
+    }
+
+    /// Returns whether the remote player left, or wasn't heard of for too long.
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected
+    }
+
+    /// Returns the first tick at which the confirmed `Simulation` differed from the one of the
+    /// remote player, if it did. The match can't be continued afterwards.
+    pub fn desync(&self) -> Option<u64> {
+        self.rollback.desync()
+    }
+
+    /// Advances the match by `dt` seconds with the given local input. Returns the `GameEvent`s
+    /// of the confirmed ticks.
+    pub fn update(
+        &mut self,
+        dt: f32,
+        input: NetInput,
+        simulation: &mut Simulation,
+    ) -> Vec<GameEvent> {
+        self.receive(dt);
+        let events = self.rollback.advance(dt, input, simulation);
+        self.send_inputs();
+        events
+    }
+
+    /// Tells the remote player that the match was left.
+    pub fn leave(&mut self) {
+        self.transport.send(&Message::Leave);
+    }
+
+    fn receive(&mut self, dt: f32) {
+        self.silence += dt;
+        while let Some(message) = self.transport.receive() {
+            self.silence = 0.0;
+            match message {
+                Message::Join => {
+                    if let Some(start) = self.start.as_ref() {
+                        self.transport.send(start);
+                    }
+                }
+                Message::Start { .. } => {}
+                Message::Inputs { ack, start, checksum, inputs } => {
+                    self.acknowledged = self.acknowledged.max(ack);
+                    self.rollback.add_remote_inputs(start, &inputs);
+                    if let Some(checksum) = checksum {
+                        self.rollback.add_remote_checksum(checksum);
+                    }
+                }
+                Message::Leave => {
+                    info!("The remote player left the match");
+                    self.disconnected = true;
+                }
+            }
+        }
+
+        if self.silence > TIMEOUT && !self.disconnected {
+            warn!("Lost the connection to the remote player");
+            self.disconnected = true;
+        }
+    }
+
+    fn send_inputs(&mut self) {
+        let inputs = self.rollback.local_inputs();
+        let start = (self.acknowledged as usize).min(inputs.len());
+        let end = (start + MAX_INPUTS_PER_MESSAGE).min(inputs.len());
+        let message = Message::Inputs {
+            ack: self.rollback.received(),
+            start: start as u64,
+            checksum: self.rollback.checksum(),
+            inputs: inputs[start..end].to_vec(),
+        };
+        self.transport.send(&message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        thread,
+        time::Duration,
+    };
+
+    use crate::{
+        config::GameConfig,
+        net::{
+            with_conditions,
+            Lobby,
+            MemoryTransport,
+        },
+        simulation::Random,
+    };
+
+    use super::*;
+
+    /// Maximum number of frames the test match may take.
+    const MAX_FRAMES: usize = 100_000;
+
+    /// Creates the session of a player, once it found the other one.
+    fn start(connection: Connection, input_delay: u64) -> (NetSession, Simulation) {
+        let simulation = Simulation::new(&connection.config, connection.seed);
+        (NetSession::new(connection, &simulation, input_delay, 12), simulation)
+    }
+
+    /// Connects a host and a joining player over a bad connection, and creates their sessions.
+    /// The session of the host starts right away and repeats the `Start` message if it got lost.
+    fn connect(config: &GameConfig) -> [(NetSession, Simulation); 2] {
+        let (host, join) = MemoryTransport::pair();
+        let mut host_lobby = Lobby::host(with_conditions(host, &config.network), config.clone(), 99)
+            .expect("failed to host");
+        let mut join_lobby = Lobby::join(with_conditions(join, &config.network));
+
+        let mut host = None;
+        for _ in 0..MAX_FRAMES {
+            match host.as_mut() {
+                None => {
+                    host = host_lobby.update(0.01).expect("failed to host").map(|connection| {
+                        start(connection, 2)
+                    });
+                }
+                Some((session, simulation)) => {
+                    session.update(0.01, NetInput::default(), simulation);
+                }
+            }
+
+            if let Some(connection) = join_lobby.update(0.01).expect("failed to join") {
+                let host = host.expect("joined before the host started");
+                return [host, start(connection, 3)];
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("players didn't find each other");
+    }
+
+    #[test]
+    fn both_players_confirm_the_same_match_over_a_bad_connection() {
+        let mut config = GameConfig::default();
+        config.match_rules.points_to_win = 3;
+        config.match_rules.best_of = 1;
+        config.network.packet_loss = 0.25;
+        config.network.latency = 0.003;
+        let mut sessions = connect(&config);
+        assert_eq!(sessions[0].0.local_player(), Player::P1);
+        assert_eq!(sessions[1].0.local_player(), Player::P2);
+
+        let mut random = Random::new(5);
+        let mut frames = 0;
+        while sessions.iter().any(|(session, _)| session.confirmed().winner().is_none()) {
+            frames += 1;
+            assert!(frames < MAX_FRAMES, "the match didn't end");
+
+            for (session, simulation) in sessions.iter_mut() {
+                // follow the ball with some error, and launch it sooner or later
+                let player = session.local_player();
+                let ball = simulation.balls()[0].position[1];
+                let offset = ball - simulation.paddle(player).position[1];
+                let movement = (offset / 10.0 + random.range(-0.5, 0.5)).max(-1.0).min(1.0);
+                let input = NetInput {
+                    movement: (movement * 127.0) as i8,
+                    launch: random.next_f32() < 0.05,
+                };
+                session.update(3.0 / 60.0, input, simulation);
+                assert!(!session.is_disconnected());
+                assert_eq!(session.desync(), None);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let (host, join) = (sessions[0].0.confirmed(), sessions[1].0.confirmed());
+        assert_eq!(host.current_tick(), join.current_tick());
+        assert_eq!(format!("{:?}", host.balls()), format!("{:?}", join.balls()));
+        for &player in [Player::P1, Player::P2].iter() {
+            assert_eq!(host.paddle(player).position, join.paddle(player).position);
+        }
+        assert_eq!(host.score(), join.score());
+        assert_eq!(host.winner(), join.winner());
+    }
+}
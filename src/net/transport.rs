@@ -0,0 +1,225 @@
+use std::{
+    io::{
+        self,
+        ErrorKind,
+    },
+    net::{
+        SocketAddr,
+        ToSocketAddrs,
+        UdpSocket,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use crate::{
+    config::NetworkConfig,
+    simulation::{
+        seed_from_time,
+        Random,
+    },
+};
+
+use super::Message;
+
+/// Maximum size of a received packet.
+const MAX_PACKET_SIZE: usize = 4096;
+
+/// A Transport delivers `Message`s to the remote player. Messages may get lost, arrive late or
+/// out of order, so everything built on top has to cope with that.
+pub trait Transport: Send + Sync {
+    fn send(&mut self, message: &Message);
+
+    /// Returns the next received message, if any, without blocking.
+    fn receive(&mut self) -> Option<Message>;
+}
+
+/// The UdpTransport exchanges messages over a non-blocking UDP socket. The host learns the
+/// address of the remote player from the first message it receives, and ignores anybody else
+/// afterwards.
+pub struct UdpTransport {
+    socket: UdpSocket,
+    peer: Option<SocketAddr>,
+}
+
+impl UdpTransport {
+    /// Waits for a player to join on the given port.
+    pub fn host(port: u16) -> io::Result<Self> {
+        Self::bind(("0.0.0.0", port), None)
+    }
+
+    /// Joins the host at the given address.
+    pub fn join(address: &str) -> io::Result<Self> {
+        let peer = address.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(ErrorKind::InvalidInput, format!("Invalid address {}", address))
+        })?;
+        Self::bind(("0.0.0.0", 0), Some(peer))
+    }
+
+    fn bind<A: ToSocketAddrs>(address: A, peer: Option<SocketAddr>) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket, peer })
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&mut self, message: &Message) {
+        if let Some(peer) = self.peer {
+            if let Err(error) = self.socket.send_to(&message.encode(), peer) {
+                warn!("Failed to send to {}: {}", peer, error);
+            }
+        }
+    }
+
+    fn receive(&mut self) -> Option<Message> {
+        let mut buffer = [0; MAX_PACKET_SIZE];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((length, sender)) => {
+                    if self.peer.map_or(false, |peer| peer != sender) {
+                        continue;
+                    }
+                    if let Some(message) = Message::decode(&buffer[..length]) {
+                        self.peer = Some(sender);
+                        return Some(message);
+                    }
+                }
+                Err(ref error) if error.kind() == ErrorKind::WouldBlock => return None,
+                // reported by some platforms if a previous packet couldn't be delivered
+                Err(ref error) if error.kind() == ErrorKind::ConnectionReset => continue,
+                Err(error) => {
+                    warn!("Failed to receive: {}", error);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// The LossyTransport simulates a bad connection on top of another `Transport`, by dropping a
+/// fraction of the sent messages and delaying the others, e.g. to test matches between two
+/// instances on localhost.
+pub struct LossyTransport<T> {
+    transport: T,
+    packet_loss: f32,
+    latency: Duration,
+    random: Random,
+    /// The delayed messages, together with the time they are due.
+    delayed: Vec<(Instant, Message)>,
+}
+
+impl<T: Transport> LossyTransport<T> {
+    pub fn new(transport: T, packet_loss: f32, latency: f32) -> Self {
+        Self {
+            transport,
+            packet_loss,
+            latency: Duration::from_millis((latency.max(0.0) * 1000.0) as u64),
+            random: Random::new(seed_from_time()),
+            delayed: Vec::new(),
+        }
+    }
+
+    /// Sends the delayed messages that are due.
+    fn flush(&mut self) {
+        let now = Instant::now();
+        let transport = &mut self.transport;
+        self.delayed.retain(|(due, message)| {
+            if *due <= now {
+                transport.send(message);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+impl<T: Transport> Transport for LossyTransport<T> {
+    fn send(&mut self, message: &Message) {
+        if self.random.next_f32() >= self.packet_loss {
+            self.delayed.push((Instant::now() + self.latency, message.clone()));
+        }
+        self.flush();
+    }
+
+    fn receive(&mut self) -> Option<Message> {
+        self.flush();
+        self.transport.receive()
+    }
+}
+
+/// Wraps the given transport into a `LossyTransport`, if the `NetworkConfig` asks for packet
+/// loss or latency.
+pub fn with_conditions<T>(transport: T, config: &NetworkConfig) -> Box<dyn Transport>
+where
+    T: Transport + 'static,
+{
+    if config.packet_loss > 0.0 || config.latency > 0.0 {
+        Box::new(LossyTransport::new(transport, config.packet_loss, config.latency))
+    } else {
+        Box::new(transport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::net::MemoryTransport;
+
+    use super::*;
+
+    #[test]
+    fn memory_transports_deliver_to_each_other_in_order() {
+        let (mut left, mut right) = MemoryTransport::pair();
+        left.send(&Message::Join);
+        left.send(&Message::Leave);
+        right.send(&Message::Start { seed: 3, config: String::new() });
+
+        assert_eq!(right.receive(), Some(Message::Join));
+        assert_eq!(right.receive(), Some(Message::Leave));
+        assert_eq!(right.receive(), None);
+        assert_eq!(left.receive(), Some(Message::Start { seed: 3, config: String::new() }));
+        assert_eq!(left.receive(), None);
+    }
+
+    #[test]
+    fn lossy_transport_drops_and_delays_messages() {
+        let (left, mut right) = MemoryTransport::pair();
+        let mut lossy = LossyTransport::new(left, 1.0, 0.0);
+        (0..100).for_each(|_| lossy.send(&Message::Join));
+        assert_eq!(right.receive(), None);
+
+        let (left, mut right) = MemoryTransport::pair();
+        let mut lossy = LossyTransport::new(left, 0.0, 0.05);
+        lossy.send(&Message::Join);
+        assert_eq!(right.receive(), None);
+
+        // delayed messages are sent with the next call once they are due
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(lossy.receive(), None);
+        assert_eq!(right.receive(), Some(Message::Join));
+    }
+
+    #[test]
+    fn conditions_are_only_simulated_if_configured() {
+        let mut config = NetworkConfig {
+            packet_loss: 0.0,
+            latency: 0.0,
+            ..NetworkConfig::default()
+        };
+        let (left, mut right) = MemoryTransport::pair();
+        let mut transport = with_conditions(left, &config);
+        transport.send(&Message::Join);
+        assert_eq!(right.receive(), Some(Message::Join));
+
+        config.packet_loss = 1.0;
+        let (left, mut right) = MemoryTransport::pair();
+        let mut transport = with_conditions(left, &config);
+        transport.send(&Message::Join);
+        assert_eq!(right.receive(), None);
+    }
+}
@@ -0,0 +1,355 @@
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::GameConfig,
+    resources::{
+        Command,
+        Player,
+        ScoreBoard,
+    },
+    simulation::Simulation,
+};
+
+/// Version of the replay file format. Replays of other versions can't be played back.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Directory, relative to the application root, the replays are written to.
+pub const REPLAY_DIR: &str = "replays";
+
+/// A ReplayStep holds the commands that were applied during a single `Simulation` step, which
+/// started at the given tick and lasted for the given number of ticks.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReplayStep {
+    pub tick: u64,
+    pub ticks: u64,
+    pub commands: Vec<Command>,
+}
+
+/// A Replay holds everything required to reproduce a match: the `GameConfig` and the seed the
+/// `Simulation` was created with, and the commands of every step. Ticks without a step were
+/// simulated without any commands. The outcome of the match is stored as well, to verify that a
+/// playback leads to the same result.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub config: GameConfig,
+    pub steps: Vec<ReplayStep>,
+    /// Number of ticks the match lasted.
+    pub ticks: u64,
+    pub score: ScoreBoard,
+    pub winner: Option<Player>,
+    /// Commands of the steps without any ticks, which apply to the next tick like the `Inputs`
+    /// they were collected in do.
+    #[serde(skip)]
+    pending: Vec<Command>,
+}
+
+impl Replay {
+    /// Create an empty `Replay` for a match with the given config and seed.
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            config,
+            steps: Vec::new(),
+            ticks: 0,
+            score: ScoreBoard::default(),
+            winner: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Records the commands of a step that ended at the given tick. Only commands that affect
+    /// the `Simulation` are kept, steps without any of them aren't recorded at all. The commands
+    /// of a step without any ticks are added to the next step.
+    pub fn record(&mut self, end_tick: u64, commands: &[Command]) {
+        self.pending.extend(
+            commands
+                .iter()
+                .filter(|command| match command {
+                    Command::MovePaddle(..) | Command::LaunchBall(..) => true,
+                    _ => false,
+                })
+                .cloned(),
+        );
+
+        if end_tick > self.ticks {
+            if !self.pending.is_empty() {
+                self.steps.push(ReplayStep {
+                    tick: self.ticks,
+                    ticks: end_tick - self.ticks,
+                    commands: self.pending.split_off(0),
+                });
+            }
+            self.ticks = end_tick;
+        }
+    }
+
+    /// Returns the commands that were applied during the given tick, together with the tick at
+    /// which they change next. As a step applies its commands to each of its ticks, the ticks of
+    /// a step may be played back one by one as well.
+    pub fn segment(&self, tick: u64) -> (&[Command], u64) {
+        // index of the first step starting after the given tick
+        let next = match self.steps.binary_search_by_key(&tick, |step| step.tick) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        };
+
+        if next > 0 {
+            let step = &self.steps[next - 1];
+            if tick < step.tick + step.ticks {
+                return (&step.commands, step.tick + step.ticks);
+            }
+        }
+
+        // no commands until the next step, or the end of the match
+        let end = self.steps.get(next).map_or(self.ticks, |step| step.tick);
+        (&[], end)
+    }
+
+    /// Stores the outcome of the match.
+    pub fn finish(&mut self, simulation: &Simulation) {
+        self.ticks = simulation.current_tick();
+        self.score = simulation.score().clone();
+        self.winner = simulation.winner();
+    }
+
+    /// Checks whether the playback of the replay led to the recorded outcome.
+    pub fn verify(&self, simulation: &Simulation) -> Result<(), String> {
+        if simulation.current_tick() != self.ticks {
+            return Err(format!(
+                "ended after {} ticks instead of {}",
+                simulation.current_tick(),
+                self.ticks,
+            ));
+        }
+        if *simulation.score() != self.score {
+            return Err(format!(
+                "ended with {:?} instead of {:?}",
+                simulation.score(),
+                self.score,
+            ));
+        }
+        if simulation.winner() != self.winner {
+            return Err(format!(
+                "was won by {:?} instead of {:?}",
+                simulation.winner(),
+                self.winner,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Loads a replay, rejecting replays of other versions and configs the game can't run with.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let replay: Replay = ron::de::from_str(&content).map_err(|error| error.to_string())?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!(
+                "Unsupported replay version {}, expected {}",
+                replay.version,
+                REPLAY_VERSION,
+            ));
+        }
+        replay.config.validate()?;
+        Ok(replay)
+    }
+
+    /// Writes the replay into the given directory, named after the seed, and returns the path of
+    /// the file.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf, String> {
+        fs::create_dir_all(&dir).map_err(|error| error.to_string())?;
+        let path = dir.as_ref().join(format!("replay-{}.ron", self.seed));
+        let content = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|error| error.to_string())?;
+        fs::write(&path, content).map_err(|error| error.to_string())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        replay::ReplayPlayback,
+        simulation::{
+            Inputs,
+            Random,
+        },
+    };
+
+    use super::*;
+
+    /// Frame durations the recorded match cycles through, including frames that are too short
+    /// for a tick.
+    const FRAMES: [f32; 4] = [1.0 / 30.0, 1.0 / 144.0, 1.0 / 500.0, 1.0 / 60.0];
+
+    fn short_match_config() -> GameConfig {
+        let mut config = GameConfig::default();
+        config.match_rules.points_to_win = 3;
+        config.match_rules.best_of = 3;
+        config
+    }
+
+    /// Plays a headless match to its end and records it, applying the commands of every frame
+    /// like the `StepSimulationSystem` and the `RecordReplaySystem` do.
+    fn record_match(config: &GameConfig, seed: u64) -> (Replay, Simulation) {
+        let mut simulation = Simulation::new(config, seed);
+        let mut replay = Replay::new(config.clone(), seed);
+        let mut random = Random::new(seed);
+        let mut inputs = Inputs::default();
+
+        let mut frame = 0;
+        while simulation.winner().is_none() {
+            assert!(frame < 1_000_000, "match didn't end");
+            let ball = simulation.balls()[0].position[1];
+            let mut commands = Vec::new();
+            for &player in [Player::P1, Player::P2].iter() {
+                let paddle = simulation.paddle(player).position[1];
+                let error = random.range(-10.0, 10.0);
+                let movement = (ball + error - paddle) / 10.0;
+                commands.push(Command::MovePaddle(player, movement));
+                if random.next_f32() < 0.02 {
+                    commands.push(Command::LaunchBall(player));
+                }
+            }
+            if random.next_f32() < 0.01 {
+                commands.push(Command::TogglePause);
+            }
+
+            let tick = simulation.current_tick();
+            commands.iter().for_each(|command| inputs.apply(command));
+            simulation.step(FRAMES[frame % FRAMES.len()], &inputs);
+            if simulation.current_tick() != tick {
+                inputs = Inputs::default();
+            }
+            replay.record(simulation.current_tick(), &commands);
+            frame += 1;
+        }
+
+        replay.finish(&simulation);
+        (replay, simulation)
+    }
+
+    #[test]
+    fn recorded_match_plays_back_to_the_same_outcome() {
+        let config = short_match_config();
+        for seed in 1..=5 {
+            let (replay, recorded) = record_match(&config, seed);
+            assert_eq!(replay.ticks, recorded.current_tick());
+            assert_eq!(replay.winner, recorded.winner());
+            assert!(replay.steps.iter().all(|step| step.ticks > 0));
+
+            let (mut playback, mut simulation) = ReplayPlayback::new(replay.clone());
+            while !playback.is_finished(simulation.current_tick()) {
+                playback.advance(0.1, &mut simulation);
+            }
+
+            assert_eq!(playback.replay().verify(&simulation), Ok(()));
+            for &player in [Player::P1, Player::P2].iter() {
+                let position = simulation.paddle(player).position;
+                assert_eq!(position, recorded.paddle(player).position);
+            }
+        }
+    }
+
+    #[test]
+    fn different_outcomes_fail_to_verify() {
+        let (replay, simulation) = record_match(&short_match_config(), 3);
+        assert_eq!(replay.verify(&simulation), Ok(()));
+
+        let mut other = replay.clone();
+        other.ticks += 1;
+        assert!(other.verify(&simulation).is_err());
+
+        let mut other = replay.clone();
+        other.score.score_left += 1;
+        assert!(other.verify(&simulation).is_err());
+
+        let mut other = replay.clone();
+        other.winner = None;
+        assert!(other.verify(&simulation).is_err());
+    }
+
+    #[test]
+    fn commands_of_frames_without_ticks_are_recorded_with_the_next_step() {
+        let mut replay = Replay::new(GameConfig::default(), 1);
+        replay.record(0, &[Command::MovePaddle(Player::P1, 1.0)]);
+        replay.record(2, &[Command::LaunchBall(Player::P2), Command::TogglePause]);
+        replay.record(3, &[]);
+        replay.record(3, &[Command::QuitToMenu]);
+        replay.record(5, &[Command::MovePaddle(Player::P2, -1.0)]);
+
+        assert_eq!(replay.ticks, 5);
+        assert_eq!(replay.steps.len(), 2);
+        assert_eq!((replay.steps[0].tick, replay.steps[0].ticks), (0, 2));
+        assert_eq!(
+            replay.steps[0].commands,
+            vec![Command::MovePaddle(Player::P1, 1.0), Command::LaunchBall(Player::P2)],
+        );
+        assert_eq!((replay.steps[1].tick, replay.steps[1].ticks), (3, 2));
+        assert_eq!(replay.steps[1].commands, vec![Command::MovePaddle(Player::P2, -1.0)]);
+    }
+
+    #[test]
+    fn segments_cover_steps_and_gaps() {
+        let first = vec![Command::MovePaddle(Player::P1, 1.0)];
+        let second = vec![Command::LaunchBall(Player::P1)];
+        let third = vec![Command::MovePaddle(Player::P2, -1.0)];
+
+        let mut replay = Replay::new(GameConfig::default(), 1);
+        replay.steps = vec![
+            ReplayStep { tick: 10, ticks: 5, commands: first.clone() },
+            ReplayStep { tick: 15, ticks: 1, commands: second.clone() },
+            ReplayStep { tick: 30, ticks: 10, commands: third.clone() },
+        ];
+        replay.ticks = 50;
+
+        // gap before the first step
+        assert_eq!(replay.segment(0), (&[][..], 10));
+        assert_eq!(replay.segment(9), (&[][..], 10));
+
+        // first and last tick of a step, directly followed by the next one
+        assert_eq!(replay.segment(10), (&first[..], 15));
+        assert_eq!(replay.segment(14), (&first[..], 15));
+        assert_eq!(replay.segment(15), (&second[..], 16));
+
+        // gap between two steps
+        assert_eq!(replay.segment(16), (&[][..], 30));
+        assert_eq!(replay.segment(29), (&[][..], 30));
+        assert_eq!(replay.segment(39), (&third[..], 40));
+
+        // gap until the end of the match
+        assert_eq!(replay.segment(40), (&[][..], 50));
+        assert_eq!(replay.segment(49), (&[][..], 50));
+    }
+
+    #[test]
+    fn load_rejects_other_versions() {
+        let dir = std::env::temp_dir().join(format!("pong-replay-test-{}", std::process::id()));
+        let (replay, _) = record_match(&short_match_config(), 7);
+        let path = replay.save(&dir).expect("failed to save the replay");
+
+        let loaded = Replay::load(&path).expect("failed to load the replay");
+        assert_eq!(loaded.seed, replay.seed);
+        assert_eq!(loaded.ticks, replay.ticks);
+        assert_eq!(loaded.steps.len(), replay.steps.len());
+        assert_eq!(loaded.score, replay.score);
+
+        let mut other = replay.clone();
+        other.version = REPLAY_VERSION + 1;
+        let path = other.save(&dir).expect("failed to save the replay");
+        let error = Replay::load(&path).expect_err("loaded a replay of another version");
+        assert!(error.contains("version"), "{}", error);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
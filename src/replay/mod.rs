@@ -0,0 +1,14 @@
+pub use self::{
+    file::{
+        Replay,
+        ReplayStep,
+        REPLAY_DIR,
+        REPLAY_VERSION,
+    },
+    playback::ReplayPlayback,
+    recorder::ReplayRecorder,
+};
+
+pub mod file;
+pub mod playback;
+pub mod recorder;
@@ -0,0 +1,280 @@
+use crate::{
+    resources::GameEvent,
+    simulation::{
+        Inputs,
+        Simulation,
+    },
+};
+
+use super::Replay;
+
+/// The maximum amount of time a single frame may play back, like the `Simulation` does.
+const MAX_FRAME_DURATION: f32 = 0.25;
+
+/// Number of ticks between two snapshots of the `Simulation`, which seeking starts from.
+const SNAPSHOT_INTERVAL: u64 = 600;
+
+/// The playback speeds to choose from.
+const SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+/// Index of the real time playback speed.
+const DEFAULT_SPEED: usize = 2;
+
+/// The ReplayPlayback plays a `Replay` back, by applying the recorded commands to the
+/// `Simulation` tick by tick. The playback can be paused, sped up or slowed down, stepped through
+/// a single tick at a time and seeked to any tick. To seek quickly, a snapshot of the
+/// `Simulation` is kept every `SNAPSHOT_INTERVAL` ticks, so only the ticks since the closest
+/// snapshot have to be simulated again.
+pub struct ReplayPlayback {
+    replay: Replay,
+    /// Time that was passed to `advance`, but not yet played back.
+    accumulator: f32,
+    paused: bool,
+    /// Index of the current playback speed.
+    speed: usize,
+    /// The `Simulation` at every multiple of `SNAPSHOT_INTERVAL` ticks played back so far.
+    snapshots: Vec<Simulation>,
+}
+
+impl ReplayPlayback {
+    /// Create a `ReplayPlayback` and the `Simulation` to play the given replay back with.
+    pub fn new(replay: Replay) -> (Self, Simulation) {
+        let simulation = Simulation::new(&replay.config, replay.seed);
+        let playback = Self {
+            replay,
+            accumulator: 0.0,
+            paused: false,
+            speed: DEFAULT_SPEED,
+            snapshots: vec![simulation.clone()],
+        };
+        (playback, simulation)
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Returns whether every recorded tick was played back.
+    pub fn is_finished(&self, current_tick: u64) -> bool {
+        current_tick >= self.replay.ticks
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.accumulator = 0.0;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.accumulator = 0.0;
+    }
+
+    /// Returns the factor the playback is sped up by.
+    pub fn speed(&self) -> f32 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Advances the playback by `dt` seconds at the current speed, unless it's paused. Returns
+    /// the `GameEvent`s of all simulated ticks.
+    pub fn advance(&mut self, dt: f32, simulation: &mut Simulation) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.paused || self.is_finished(simulation.current_tick()) {
+            return events;
+        }
+
+        self.accumulator += dt.min(MAX_FRAME_DURATION) * self.speed();
+        let ticks = (self.accumulator / simulation.tick_duration()) as u64;
+        self.accumulator -= ticks as f32 * simulation.tick_duration();
+
+        let target = simulation.current_tick() + ticks;
+        self.play(simulation, target, &mut events);
+        events
+    }
+
+    /// Plays back a single tick, e.g. while the playback is paused. Returns the `GameEvent`s of
+    /// the tick.
+    pub fn step(&mut self, simulation: &mut Simulation) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let target = simulation.current_tick() + 1;
+        self.play(simulation, target, &mut events);
+        events
+    }
+
+    /// Moves the playback to the given tick, starting from the closest snapshot before it.
+    pub fn seek(&mut self, simulation: &mut Simulation, tick: u64) {
+        let tick = tick.min(self.replay.ticks);
+        let snapshot = ((tick / SNAPSHOT_INTERVAL) as usize).min(self.snapshots.len() - 1);
+
+        // seeking forward may continue from the current tick, if it is closer than the snapshot
+        let current = simulation.current_tick();
+        if tick < current || current < snapshot as u64 * SNAPSHOT_INTERVAL {
+            *simulation = self.snapshots[snapshot].clone();
+        }
+        self.accumulator = 0.0;
+        self.play(simulation, tick, &mut Vec::new());
+    }
+
+    /// Simulates the ticks up to the given one, limited to the recorded ticks, applying the
+    /// recorded commands and taking snapshots along the way.
+    fn play(&mut self, simulation: &mut Simulation, target: u64, events: &mut Vec<GameEvent>) {
+        let target = target.min(self.replay.ticks);
+        while simulation.current_tick() < target {
+            let tick = simulation.current_tick();
+            let (commands, end) = self.replay.segment(tick);
+            let next_snapshot = (tick / SNAPSHOT_INTERVAL + 1) * SNAPSHOT_INTERVAL;
+
+            let mut inputs = Inputs::default();
+            commands.iter().for_each(|command| inputs.apply(command));
+            simulation.advance(end.min(target).min(next_snapshot) - tick, &inputs);
+            events.extend(simulation.events().iter().cloned());
+
+            // a match that is over doesn't advance anymore, e.g. if the playback diverged
+            if simulation.current_tick() == tick {
+                break;
+            }
+
+            let current = simulation.current_tick();
+            if current % SNAPSHOT_INTERVAL == 0
+                && current / SNAPSHOT_INTERVAL == self.snapshots.len() as u64 {
+                self.snapshots.push(simulation.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::GameConfig,
+        resources::{
+            Command,
+            Player,
+        },
+        simulation::Random,
+    };
+
+    use super::*;
+
+    /// Records a replay of the given number of ticks, with random commands held for a random
+    /// number of ticks.
+    fn record(seed: u64, ticks: u64) -> Replay {
+        let config = GameConfig::default();
+        let mut simulation = Simulation::new(&config, seed);
+        let mut replay = Replay::new(config, seed);
+        let mut random = Random::new(seed);
+
+        while simulation.current_tick() < ticks && simulation.winner().is_none() {
+            let mut commands = Vec::new();
+            for &player in [Player::P1, Player::P2].iter() {
+                if random.next_f32() < 0.8 {
+                    commands.push(Command::MovePaddle(player, random.range(-1.0, 1.0)));
+                }
+                if random.next_f32() < 0.3 {
+                    commands.push(Command::LaunchBall(player));
+                }
+            }
+
+            let mut inputs = Inputs::default();
+            commands.iter().for_each(|command| inputs.apply(command));
+            let length = 1 + (random.next_f32() * 20.0) as u64;
+            simulation.advance(length.min(ticks - simulation.current_tick()), &inputs);
+            replay.record(simulation.current_tick(), &commands);
+        }
+
+        replay.finish(&simulation);
+        replay
+    }
+
+    /// Returns everything about the state of the simulation that the playback depends on.
+    fn state(simulation: &Simulation) -> String {
+        format!(
+            "{} {:?} {:?} {:?} {:?} {:?} {:?}",
+            simulation.current_tick(),
+            simulation.balls(),
+            simulation.paddle(Player::P1),
+            simulation.paddle(Player::P2),
+            simulation.score(),
+            simulation.rally(),
+            simulation.winner(),
+        )
+    }
+
+    /// Plays the replay straight through to the given tick, one tick at a time.
+    fn played_through(replay: &Replay, tick: u64) -> String {
+        let (mut playback, mut simulation) = ReplayPlayback::new(replay.clone());
+        while simulation.current_tick() < tick.min(replay.ticks) {
+            playback.step(&mut simulation);
+        }
+        state(&simulation)
+    }
+
+    #[test]
+    fn seeking_matches_playing_straight_through() {
+        let replay = record(11, 3000);
+        assert_eq!(replay.ticks, 3000);
+        let (mut playback, mut simulation) = ReplayPlayback::new(replay.clone());
+
+        // forward without any snapshots beyond the start
+        playback.seek(&mut simulation, 1234);
+        assert_eq!(state(&simulation), played_through(&replay, 1234));
+
+        // backward from ahead of the target, starting from a snapshot
+        playback.seek(&mut simulation, 2500);
+        playback.seek(&mut simulation, 700);
+        assert_eq!(state(&simulation), played_through(&replay, 700));
+
+        // forward from behind the target, past the closest snapshot
+        playback.seek(&mut simulation, 10);
+        playback.seek(&mut simulation, 1900);
+        assert_eq!(state(&simulation), played_through(&replay, 1900));
+
+        // exactly onto a snapshot, and back to the start
+        playback.seek(&mut simulation, 1200);
+        assert_eq!(state(&simulation), played_through(&replay, 1200));
+        playback.seek(&mut simulation, 0);
+        assert_eq!(state(&simulation), played_through(&replay, 0));
+    }
+
+    #[test]
+    fn seeking_past_the_end_stops_at_the_last_tick() {
+        let replay = record(5, 1500);
+        let (mut playback, mut simulation) = ReplayPlayback::new(replay.clone());
+
+        playback.seek(&mut simulation, replay.ticks + 1000);
+        assert_eq!(simulation.current_tick(), replay.ticks);
+        assert_eq!(state(&simulation), played_through(&replay, replay.ticks));
+        assert!(playback.is_finished(simulation.current_tick()));
+
+        // again, from behind the end
+        playback.seek(&mut simulation, 100);
+        playback.seek(&mut simulation, std::u64::MAX);
+        assert_eq!(state(&simulation), played_through(&replay, replay.ticks));
+    }
+
+    #[test]
+    fn seeking_matches_playing_back_in_real_time() {
+        let replay = record(23, 2000);
+        let (mut playback, mut simulation) = ReplayPlayback::new(replay.clone());
+        while simulation.current_tick() < 1500 {
+            playback.advance(1.0 / 60.0, &mut simulation);
+        }
+        let tick = simulation.current_tick();
+        let played = state(&simulation);
+
+        playback.seek(&mut simulation, 300);
+        playback.seek(&mut simulation, tick);
+        assert_eq!(state(&simulation), played);
+    }
+}
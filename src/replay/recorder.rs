@@ -0,0 +1,25 @@
+use super::Replay;
+
+/// The ReplayRecorder holds the `Replay` of the current match while it is being recorded. Matches
+/// without a replay, such as demos, aren't recorded.
+#[derive(Default)]
+pub struct ReplayRecorder {
+    replay: Option<Replay>,
+}
+
+impl ReplayRecorder {
+    /// Starts recording into the given replay, dropping any previous recording.
+    pub fn start(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+    }
+
+    /// Returns the replay being recorded, if any.
+    pub fn replay_mut(&mut self) -> Option<&mut Replay> {
+        self.replay.as_mut()
+    }
+
+    /// Stops recording and returns the recorded replay, if any.
+    pub fn stop(&mut self) -> Option<Replay> {
+        self.replay.take()
+    }
+}
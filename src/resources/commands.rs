@@ -1,13 +1,25 @@
 use amethyst::shrev::EventChannel;
+use serde::{Deserialize, Serialize};
 
 use super::players::Player;
 
-/// List of commands that are interpreted by systems.
-#[derive(Debug)]
+/// List of commands that are interpreted by systems and states.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum Command {
     MovePaddle(Player, f32),
-    //LaunchBall(Player),
-    //Pause,
+    LaunchBall(Player),
+    TogglePause,
+    QuitToMenu,
+}
+
+impl Command {
+    /// Returns the player the command is issued for, if any.
+    pub fn player(&self) -> Option<Player> {
+        match *self {
+            Command::MovePaddle(player, _) | Command::LaunchBall(player) => Some(player),
+            _ => None,
+        }
+    }
 }
 
 /// Custom type alias for EventChannel<Command>. Mostly for convenience.
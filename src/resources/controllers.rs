@@ -0,0 +1,143 @@
+use super::{
+    players::Player,
+    script::Script,
+};
+
+/// KeyBindings enumeration describes which bindings of the input config a keyboard controller
+/// uses.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyBindings {
+    Left,
+    Right,
+}
+
+impl KeyBindings {
+    /// Returns the bindings on the side of the given player.
+    pub fn of(player: Player) -> Self {
+        match player {
+            Player::P1 => KeyBindings::Left,
+            Player::P2 => KeyBindings::Right,
+        }
+    }
+
+    /// Returns the name of the axis moving the paddle.
+    pub fn axis(self) -> &'static str {
+        match self {
+            KeyBindings::Left => "left_paddle",
+            KeyBindings::Right => "right_paddle",
+        }
+    }
+
+    /// Returns the name of the action launching the ball.
+    pub fn launch_action(self) -> &'static str {
+        match self {
+            KeyBindings::Left => "left_launch",
+            KeyBindings::Right => "right_launch",
+        }
+    }
+}
+
+/// ControllerKind enumeration describes who or what controls a player.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ControllerKind {
+    /// A human, using the given keyboard bindings.
+    Keyboard(KeyBindings),
+    /// A human, using the gamepad assigned to the player in `Gamepads`.
+    Gamepad,
+    /// A human, moving the paddle with the mouse.
+    Mouse,
+    /// The ai, playing with the difficulty from the `GameConfig`.
+    Ai,
+    /// A human playing on another instance, whose inputs arrive over the network.
+    Remote,
+    /// The commands of the `Script` resource, e.g. to play a prepared situation.
+    Scripted,
+}
+
+/// PlayerControllers assigns a `ControllerKind` to every player. Changes take effect with the
+/// next frame.
+#[derive(Clone, Debug)]
+pub struct PlayerControllers {
+    pub p1: ControllerKind,
+    pub p2: ControllerKind,
+}
+
+impl PlayerControllers {
+    /// A human on the keyboard against the ai, playing the given player.
+    pub fn single_player(human: Player) -> Self {
+        let mut controllers = Self::demo();
+        controllers.set(human, ControllerKind::Keyboard(KeyBindings::of(human)));
+        controllers
+    }
+
+    /// Two humans sharing the keyboard.
+    pub fn multi_player() -> Self {
+        Self {
+            p1: ControllerKind::Keyboard(KeyBindings::Left),
+            p2: ControllerKind::Keyboard(KeyBindings::Right),
+        }
+    }
+
+    /// A human on the keyboard against a human on another instance.
+    pub fn network(local: Player) -> Self {
+        let mut controllers = Self {
+            p1: ControllerKind::Remote,
+            p2: ControllerKind::Remote,
+        };
+        controllers.set(local, ControllerKind::Keyboard(KeyBindings::of(local)));
+        controllers
+    }
+
+    /// The ai playing against itself.
+    pub fn demo() -> Self {
+        Self {
+            p1: ControllerKind::Ai,
+            p2: ControllerKind::Ai,
+        }
+    }
+
+    /// Lets the given `Script` play the players it holds commands for, instead of their
+    /// previous controller.
+    pub fn with_script(mut self, script: &Script) -> Self {
+        for &player in [Player::P1, Player::P2].iter() {
+            if script.controls(player) {
+                self.set(player, ControllerKind::Scripted);
+            }
+        }
+        self
+    }
+
+    /// Lets the given player control their paddle with the mouse, unless they are played by the
+    /// ai or on another instance.
+    pub fn with_mouse(mut self, player: Option<Player>) -> Self {
+        if let Some(player) = player {
+            match self.get(player) {
+                ControllerKind::Ai | ControllerKind::Remote => {}
+                _ => self.set(player, ControllerKind::Mouse),
+            }
+        }
+        self
+    }
+
+    /// Returns the controller assigned to the given player.
+    pub fn get(&self, player: Player) -> ControllerKind {
+        match player {
+            Player::P1 => self.p1,
+            Player::P2 => self.p2,
+        }
+    }
+
+    /// Assigns a controller to the given player.
+    pub fn set(&mut self, player: Player, kind: ControllerKind) {
+        match player {
+            Player::P1 => self.p1 = kind,
+            Player::P2 => self.p2 = kind,
+        }
+    }
+}
+
+impl Default for PlayerControllers {
+    fn default() -> Self {
+        PlayerControllers::multi_player()
+    }
+}
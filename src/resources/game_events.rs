@@ -0,0 +1,35 @@
+use amethyst::shrev::EventChannel;
+
+use super::players::Player;
+
+/// List of events announcing what happened inside the `Simulation`, e.g. for sounds, effects or
+/// statistics. They are published by the `StepSimulationSystem` after every step.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    /// A ball hit the paddle of the given player at the given position, leaving it with the given
+    /// speed.
+    PaddleHit {
+        player: Player,
+        position: [f32; 2],
+        speed: f32,
+    },
+    /// A ball bounced off the top or bottom of the arena at the given position.
+    WallBounce {
+        position: [f32; 2],
+    },
+    /// The given player scored a point.
+    PointScored {
+        player: Player,
+    },
+    /// The given player served a ball.
+    ServeStarted {
+        player: Player,
+    },
+    /// The given player won the match.
+    MatchWon {
+        player: Player,
+    },
+}
+
+/// Custom type alias for EventChannel<GameEvent>. Mostly for convenience.
+pub type GameEventChannel = EventChannel<GameEvent>;
@@ -1,11 +0,0 @@
-#[derive(PartialEq)]
-pub enum GameMode {
-    SinglePlayer,
-    MultiPlayer,
-}
-
-impl Default for GameMode {
-    fn default() -> Self {
-        GameMode::MultiPlayer
-    }
-}
\ No newline at end of file
@@ -0,0 +1,294 @@
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    controllers::{
+        ControllerKind,
+        KeyBindings,
+        PlayerControllers,
+    },
+    players::Player,
+};
+
+/// Identifies a connected gamepad. Ids are handed out by the `GamepadSource` and may be reused
+/// once a gamepad was disconnected.
+pub type GamepadId = usize;
+
+/// Enumeration of the gamepad buttons, named after their position on the gamepad.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Enumeration of the gamepad axes. Values range from -1.0 to 1.0, up and right are positive.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+}
+
+/// Events reported by a `GamepadSource`.
+#[derive(Clone, Debug)]
+pub enum GamepadEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+    ButtonPressed(GamepadId, GamepadButton),
+    ButtonReleased(GamepadId, GamepadButton),
+    AxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+/// The current state of a single gamepad.
+#[derive(Clone, Debug, Default)]
+pub struct GamepadState {
+    axes: HashMap<GamepadAxis, f32>,
+    /// Buttons that are held down.
+    down: HashSet<GamepadButton>,
+    /// Buttons that were pressed during the current frame.
+    pressed: HashSet<GamepadButton>,
+}
+
+impl GamepadState {
+    /// Returns the value of the given axis.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).cloned().unwrap_or(0.0)
+    }
+
+    /// Returns whether the given button is held down.
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.down.contains(&button)
+    }
+
+    /// Returns whether the given button was pressed during the current frame.
+    pub fn was_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+}
+
+/// Gamepads holds the state of all connected gamepads, as well as the gamepad assigned to each
+/// player. Gamepads are assigned to the human players in the order they are connected: as long as
+/// a player uses a gamepad, its controller is switched to `ControllerKind::Gamepad`, and back to
+/// the keyboard once the gamepad is disconnected.
+#[derive(Clone, Debug, Default)]
+pub struct Gamepads {
+    pads: BTreeMap<GamepadId, GamepadState>,
+    /// The gamepad assigned to player 1 and player 2.
+    assignments: [Option<GamepadId>; 2],
+}
+
+impl Gamepads {
+    /// Forget the buttons pressed during the last frame.
+    pub fn begin_frame(&mut self) {
+        self.pads.values_mut().for_each(|pad| pad.pressed.clear());
+    }
+
+    /// Applies an event of a `GamepadSource`.
+    pub fn handle_event(&mut self, event: &GamepadEvent) {
+        match *event {
+            GamepadEvent::Connected(id) => {
+                info!("Gamepad {} connected", id);
+                self.pads.insert(id, GamepadState::default());
+            }
+            GamepadEvent::Disconnected(id) => {
+                info!("Gamepad {} disconnected", id);
+                self.pads.remove(&id);
+            }
+            GamepadEvent::ButtonPressed(id, button) => {
+                if let Some(pad) = self.pads.get_mut(&id) {
+                    pad.down.insert(button);
+                    pad.pressed.insert(button);
+                }
+            }
+            GamepadEvent::ButtonReleased(id, button) => {
+                if let Some(pad) = self.pads.get_mut(&id) {
+                    pad.down.remove(&button);
+                }
+            }
+            GamepadEvent::AxisChanged(id, axis, value) => {
+                if let Some(pad) = self.pads.get_mut(&id) {
+                    pad.axes.insert(axis, value);
+                }
+            }
+        }
+    }
+
+    /// Returns the states of all connected gamepads.
+    pub fn pads(&self) -> impl Iterator<Item = &GamepadState> {
+        self.pads.values()
+    }
+
+    /// Returns the id of the gamepad assigned to the given player.
+    pub fn assigned_id(&self, player: Player) -> Option<GamepadId> {
+        self.assignments[player_index(player)]
+    }
+
+    /// Returns the state of the gamepad assigned to the given player.
+    pub fn assigned(&self, player: Player) -> Option<&GamepadState> {
+        self.assigned_id(player).and_then(|id| self.pads.get(&id))
+    }
+
+    /// Brings the assignments in line with the connected gamepads and the `PlayerControllers`:
+    /// disconnected gamepads are released, and unassigned gamepads are handed to the players
+    /// waiting for a gamepad first, then to the players controlled by the keyboard. Players
+    /// that are left without a gamepad fall back to the keyboard. The ai keeps its players.
+    pub fn update_assignments(&mut self, controllers: &mut PlayerControllers) {
+        let players = [Player::P1, Player::P2];
+
+        // release gamepads which were disconnected, or whose player got another controller,
+        // e.g. because a new match was started from the menu
+        for &player in players.iter() {
+            let index = player_index(player);
+            if let Some(id) = self.assignments[index] {
+                let connected = self.pads.contains_key(&id);
+                if !connected || controllers.get(player) != ControllerKind::Gamepad {
+                    self.assignments[index] = None;
+                }
+            }
+        }
+
+        let unassigned: Vec<GamepadId> = self.pads
+            .keys()
+            .filter(|id| !self.assignments.contains(&Some(**id)))
+            .cloned()
+            .collect();
+        for id in unassigned {
+            let waiting = players.iter().cloned().find(|&player| {
+                controllers.get(player) == ControllerKind::Gamepad
+                    && self.assignments[player_index(player)].is_none()
+            });
+            let keyboard = players.iter().cloned().find(|&player| {
+                match controllers.get(player) {
+                    ControllerKind::Keyboard(_) => true,
+                    _ => false,
+                }
+            });
+
+            match waiting.or(keyboard) {
+                Some(player) => {
+                    info!("Gamepad {} controls {:?}", id, player);
+                    self.assignments[player_index(player)] = Some(id);
+                    controllers.set(player, ControllerKind::Gamepad);
+                }
+                None => break,
+            }
+        }
+
+        // fall back to the keyboard
+        for &player in players.iter() {
+            let unassigned = self.assignments[player_index(player)].is_none();
+            if controllers.get(player) == ControllerKind::Gamepad && unassigned {
+                controllers.set(player, ControllerKind::Keyboard(KeyBindings::of(player)));
+            }
+        }
+    }
+}
+
+fn player_index(player: Player) -> usize {
+    match player {
+        Player::P1 => 0,
+        Player::P2 => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gamepad::{
+        GamepadSource,
+        SimulatedGamepads,
+    };
+
+    use super::*;
+
+    /// Pushes the events through a `SimulatedGamepads` source, like the `GamepadInputSystem`
+    /// does, and updates the assignments.
+    fn apply(
+        gamepads: &mut Gamepads,
+        controllers: &mut PlayerControllers,
+        events: &[GamepadEvent],
+    ) {
+        let mut source = SimulatedGamepads::default();
+        events.iter().for_each(|event| source.push(event.clone()));
+
+        let mut polled = Vec::new();
+        source.poll(&mut polled);
+        polled.iter().for_each(|event| gamepads.handle_event(event));
+        gamepads.update_assignments(controllers);
+    }
+
+    #[test]
+    fn connected_gamepad_replaces_the_keyboard() {
+        let mut gamepads = Gamepads::default();
+        let mut controllers = PlayerControllers::single_player(Player::P1);
+        apply(&mut gamepads, &mut controllers, &[GamepadEvent::Connected(3)]);
+
+        assert_eq!(controllers.get(Player::P1), ControllerKind::Gamepad);
+        assert_eq!(gamepads.assigned_id(Player::P1), Some(3));
+        assert_eq!(controllers.get(Player::P2), ControllerKind::Ai);
+        assert_eq!(gamepads.assigned_id(Player::P2), None);
+    }
+
+    #[test]
+    fn disconnected_gamepad_falls_back_to_the_keyboard() {
+        let mut gamepads = Gamepads::default();
+        let mut controllers = PlayerControllers::single_player(Player::P2);
+        apply(&mut gamepads, &mut controllers, &[GamepadEvent::Connected(0)]);
+        assert_eq!(controllers.get(Player::P2), ControllerKind::Gamepad);
+
+        apply(&mut gamepads, &mut controllers, &[GamepadEvent::Disconnected(0)]);
+        let keyboard = ControllerKind::Keyboard(KeyBindings::of(Player::P2));
+        assert_eq!(controllers.get(Player::P2), keyboard);
+        assert_eq!(gamepads.assigned_id(Player::P2), None);
+        assert!(gamepads.assigned(Player::P2).is_none());
+    }
+
+    #[test]
+    fn ai_players_are_never_taken_over() {
+        let mut gamepads = Gamepads::default();
+        let mut controllers = PlayerControllers::demo();
+        let events = [GamepadEvent::Connected(0), GamepadEvent::Connected(1)];
+        apply(&mut gamepads, &mut controllers, &events);
+
+        for &player in [Player::P1, Player::P2].iter() {
+            assert_eq!(controllers.get(player), ControllerKind::Ai);
+            assert_eq!(gamepads.assigned_id(player), None);
+        }
+        assert_eq!(gamepads.pads().count(), 2);
+    }
+
+    #[test]
+    fn second_gamepad_goes_to_the_second_player() {
+        let mut gamepads = Gamepads::default();
+        let mut controllers = PlayerControllers::multi_player();
+        apply(&mut gamepads, &mut controllers, &[GamepadEvent::Connected(5)]);
+        assert_eq!(gamepads.assigned_id(Player::P1), Some(5));
+        assert_eq!(controllers.get(Player::P2), ControllerKind::Keyboard(KeyBindings::Right));
+
+        apply(&mut gamepads, &mut controllers, &[GamepadEvent::Connected(2)]);
+        assert_eq!(gamepads.assigned_id(Player::P1), Some(5));
+        assert_eq!(gamepads.assigned_id(Player::P2), Some(2));
+        assert_eq!(controllers.get(Player::P1), ControllerKind::Gamepad);
+        assert_eq!(controllers.get(Player::P2), ControllerKind::Gamepad);
+
+        // a third gamepad is connected, but not assigned
+        apply(&mut gamepads, &mut controllers, &[GamepadEvent::Connected(7)]);
+        assert_eq!(gamepads.assigned_id(Player::P1), Some(5));
+        assert_eq!(gamepads.assigned_id(Player::P2), Some(2));
+    }
+}
@@ -0,0 +1,17 @@
+/// MatchRequest is handed down to the `GameState` by the states that are pushed on top of it
+/// (e.g. `GameOverState`), to tell it what to do with the current match once they are popped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchRequest {
+    /// Continue the current match.
+    Continue,
+    /// Start a new match with the same settings.
+    Restart,
+    /// Leave the match and return to the `MenuState`.
+    MainMenu,
+}
+
+impl Default for MatchRequest {
+    fn default() -> Self {
+        MatchRequest::Continue
+    }
+}
\ No newline at end of file
@@ -3,18 +3,40 @@ pub use self::{
         Command,
         CommandChannel,
     },
-    game_mode::GameMode,
+    controllers::{
+        ControllerKind,
+        KeyBindings,
+        PlayerControllers,
+    },
+    game_events::{
+        GameEvent,
+        GameEventChannel,
+    },
+    gamepads::{
+        GamepadAxis,
+        GamepadButton,
+        GamepadEvent,
+        GamepadId,
+        Gamepads,
+    },
+    match_request::MatchRequest,
+    mouse::MouseCursor,
     players::{
         Player,
         Players,
     },
-    score_board::{
-        ScoreBoard,
-        ScoreText,
-    },
+    rally::Rally,
+    score_board::ScoreBoard,
+    script::Script,
 };
 
 pub mod commands;
-pub mod game_mode;
+pub mod controllers;
+pub mod game_events;
+pub mod gamepads;
+pub mod match_request;
+pub mod mouse;
 pub mod players;
+pub mod rally;
 pub mod score_board;
+pub mod script;
@@ -0,0 +1,6 @@
+/// MouseCursor holds the position of the mouse cursor in arena units, or `None` while the cursor
+/// is outside of the window. It's updated every frame by the `MouseCursorSystem`.
+#[derive(Clone, Debug, Default)]
+pub struct MouseCursor {
+    pub position: Option<[f32; 2]>,
+}
@@ -1,12 +1,23 @@
 use amethyst::ecs::prelude::Entity;
+use serde::{Deserialize, Serialize};
 
 /// Enumeration of valid players.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum Player {
     P1,
     P2,
 }
 
+impl Player {
+    /// Returns the opposing player.
+    pub fn opponent(self) -> Player {
+        match self {
+            Player::P1 => Player::P2,
+            Player::P2 => Player::P1,
+        }
+    }
+}
+
 /// Holds the entities referring to a player. A player entity generally consists of a paddle
 /// and a transformation component.
 pub struct Players {
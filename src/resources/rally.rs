@@ -0,0 +1,20 @@
+/// Rally keeps track of the number of paddle hits since the last point was scored, as well as
+/// the longest rally of the current match.
+#[derive(Clone, Debug, Default)]
+pub struct Rally {
+    pub length: u32,
+    pub longest: u32,
+}
+
+impl Rally {
+    /// Counts a paddle hit towards the current rally.
+    pub fn add_hit(&mut self) {
+        self.length += 1;
+        self.longest = self.longest.max(self.length);
+    }
+
+    /// Ends the current rally, e.g. because a point was scored.
+    pub fn reset(&mut self) {
+        self.length = 0;
+    }
+}
\ No newline at end of file
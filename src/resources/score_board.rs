@@ -1,14 +1,53 @@
-use amethyst::ecs::prelude::Entity;
+use serde::{Deserialize, Serialize};
 
-/// ScoreBoard contains the actual score data.
-#[derive(Default)]
+use super::players::Player;
+
+/// ScoreBoard contains the actual score data, i.e. the points of the current game and the number
+/// of games won by each player.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct ScoreBoard {
     pub score_left: i32,
     pub score_right: i32,
+    pub games_left: i32,
+    pub games_right: i32,
 }
 
-/// ScoreTest contains the ui text components that display the score.
-pub struct ScoreText {
-    pub p1_score: Entity,
-    pub p2_score: Entity,
-}
\ No newline at end of file
+impl ScoreBoard {
+    /// Awards a point to the given player.
+    pub fn add_point(&mut self, player: Player) {
+        match player {
+            Player::P1 => self.score_left += 1,
+            Player::P2 => self.score_right += 1,
+        }
+    }
+
+    /// Awards a game to the given player.
+    pub fn add_game(&mut self, player: Player) {
+        match player {
+            Player::P1 => self.games_left += 1,
+            Player::P2 => self.games_right += 1,
+        }
+    }
+
+    /// Resets the points for the next game.
+    pub fn reset_points(&mut self) {
+        self.score_left = 0;
+        self.score_right = 0;
+    }
+
+    /// Returns the points of the given player in the current game.
+    pub fn points(&self, player: Player) -> i32 {
+        match player {
+            Player::P1 => self.score_left,
+            Player::P2 => self.score_right,
+        }
+    }
+
+    /// Returns the number of games won by the given player.
+    pub fn games(&self, player: Player) -> i32 {
+        match player {
+            Player::P1 => self.games_left,
+            Player::P2 => self.games_right,
+        }
+    }
+}
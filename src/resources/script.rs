@@ -0,0 +1,90 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use super::{
+    commands::Command,
+    players::Player,
+};
+
+/// A Script holds commands scheduled for certain ticks of the `Simulation`, which are published
+/// by the `ControllerKind::Scripted` controllers, e.g. to reproduce a situation without having to
+/// play it. Scripts are loaded from RON files holding a list of `(tick, command)` pairs.
+#[derive(Clone, Debug, Default)]
+pub struct Script {
+    /// The commands, ordered by tick.
+    commands: Vec<(u64, Command)>,
+}
+
+impl Script {
+    pub fn new(mut commands: Vec<(u64, Command)>) -> Self {
+        // the sort is stable, so commands of the same tick keep their order
+        commands.sort_by_key(|&(tick, _)| tick);
+        Self { commands }
+    }
+
+    /// Loads a script from the given RON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let commands = ron::de::from_str(&content).map_err(|error| error.to_string())?;
+        Ok(Script::new(commands))
+    }
+
+    /// Returns whether the script holds any commands for the given player.
+    pub fn controls(&self, player: Player) -> bool {
+        self.commands.iter().any(|(_, command)| command.player() == Some(player))
+    }
+
+    /// Returns the commands of the given player scheduled from tick `start` up to, but not
+    /// including, tick `end`.
+    pub fn commands(&self, player: Player, start: u64, end: u64) -> impl Iterator<Item = &Command> {
+        self.commands
+            .iter()
+            .skip_while(move |&&(tick, _)| tick < start)
+            .take_while(move |&&(tick, _)| tick < end)
+            .map(|(_, command)| command)
+            .filter(move |command| command.player() == Some(player))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_ordered_by_tick_and_filtered_by_player() {
+        let script = Script::new(vec![
+            (10, Command::LaunchBall(Player::P1)),
+            (5, Command::MovePaddle(Player::P1, 1.0)),
+            (5, Command::MovePaddle(Player::P2, -1.0)),
+            (5, Command::MovePaddle(Player::P1, 0.5)),
+            (20, Command::MovePaddle(Player::P1, 0.0)),
+        ]);
+
+        assert!(script.controls(Player::P1));
+        assert!(script.controls(Player::P2));
+        assert!(!Script::new(vec![(0, Command::TogglePause)]).controls(Player::P1));
+
+        let commands: Vec<&Command> = script.commands(Player::P1, 0, 11).collect();
+        assert_eq!(commands, vec![
+            &Command::MovePaddle(Player::P1, 1.0),
+            &Command::MovePaddle(Player::P1, 0.5),
+            &Command::LaunchBall(Player::P1),
+        ]);
+        assert_eq!(script.commands(Player::P1, 6, 10).count(), 0);
+        assert_eq!(script.commands(Player::P1, 10, 21).count(), 2);
+        assert_eq!(script.commands(Player::P2, 0, 100).count(), 1);
+    }
+
+    #[test]
+    fn scripts_are_read_from_ron() {
+        let commands: Vec<(u64, Command)> =
+            ron::de::from_str("[(120, LaunchBall(P2)), (0, MovePaddle(P2, -1.0))]").unwrap();
+        let script = Script::new(commands);
+        assert_eq!(
+            script.commands(Player::P2, 0, 200).collect::<Vec<_>>(),
+            vec![&Command::MovePaddle(Player::P2, -1.0), &Command::LaunchBall(Player::P2)],
+        );
+    }
+}
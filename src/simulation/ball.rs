@@ -0,0 +1,29 @@
+use crate::resources::Player;
+
+/// The BallBody is the simulated representation of a ball inside the arena, such as its position,
+/// velocity and radius. While the ball is waiting to be served, it holds the pending `Serve`.
+#[derive(Clone, Debug)]
+pub struct BallBody {
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub radius: f32,
+    pub serve: Option<Serve>,
+    /// Whether the ball left the arena and waits to be served again, see `RespawnRule`.
+    pub removed: bool,
+}
+
+impl BallBody {
+    /// Returns whether the ball is moving through the arena, i.e. it was served and didn't leave
+    /// the arena yet.
+    pub fn in_play(&self) -> bool {
+        self.serve.is_none() && !self.removed
+    }
+}
+
+/// A Serve describes a ball that is held until the serving player launches it, or until the
+/// remaining ticks of the timeout ran out.
+#[derive(Clone, Copy, Debug)]
+pub struct Serve {
+    pub player: Player,
+    pub ticks_left: Option<u64>,
+}
\ No newline at end of file
@@ -0,0 +1,379 @@
+/// A Hit describes the first contact of a moving object with a surface. The time is the fraction
+/// of the movement after which the contact occurs and the normal is the surface normal at the
+/// contact point.
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    pub time: f32,
+    pub normal: [f32; 2],
+}
+
+/// Sweeps a circle along `motion` against an axis aligned box, given by its bottom left (`min`)
+/// and top right (`max`) corners, and returns the first contact within the movement.
+///
+/// Sweeping a circle against a box is the same as casting a ray from the centre of the circle
+/// against the box inflated by the radius, with rounded corners. Circles that already overlap
+/// the box or move away from it don't produce a hit.
+pub fn sweep_circle_aabb(
+    center: [f32; 2],
+    radius: f32,
+    motion: [f32; 2],
+    min: [f32; 2],
+    max: [f32; 2],
+) -> Option<Hit> {
+    let mut t_enter = std::f32::NEG_INFINITY;
+    let mut t_exit = std::f32::INFINITY;
+    let mut normal = [0.0, 0.0];
+
+    // intersect the ray with the slabs of the inflated box
+    for axis in 0..2 {
+        let low = min[axis] - radius;
+        let high = max[axis] + radius;
+
+        if motion[axis].abs() <= std::f32::EPSILON {
+            // the ray is parallel to the slab, so it has to start inside of it
+            if center[axis] < low || center[axis] > high {
+                return None;
+            }
+        } else {
+            let (t_low, t_high) = (
+                (low - center[axis]) / motion[axis],
+                (high - center[axis]) / motion[axis],
+            );
+            let (t_near, t_far, direction) = if t_low < t_high {
+                (t_low, t_high, -1.0)
+            } else {
+                (t_high, t_low, 1.0)
+            };
+
+            if t_near > t_enter {
+                t_enter = t_near;
+                normal = [0.0, 0.0];
+                normal[axis] = direction;
+            }
+            t_exit = t_exit.min(t_far);
+        }
+    }
+
+    if t_enter > t_exit || t_enter < 0.0 || t_enter > 1.0 || dot(normal, motion) >= 0.0 {
+        return None;
+    }
+
+    // If the entry point lies next to a corner of the original box, the ray hit the square
+    // corner of the inflated box. The actual shape is rounded there, so we check against a
+    // circle around the corner instead.
+    let point = [center[0] + motion[0] * t_enter, center[1] + motion[1] * t_enter];
+    let corner_x = nearest_outside(point[0], min[0], max[0]);
+    let corner_y = nearest_outside(point[1], min[1], max[1]);
+
+    match (corner_x, corner_y) {
+        (Some(x), Some(y)) => sweep_circle_point(center, radius, motion, [x, y]),
+        _ => Some(Hit { time: t_enter, normal }),
+    }
+}
+
+/// Sweeps a circle along `motion` against a single point and returns the first contact within
+/// the movement.
+pub fn sweep_circle_point(
+    center: [f32; 2],
+    radius: f32,
+    motion: [f32; 2],
+    point: [f32; 2],
+) -> Option<Hit> {
+    let offset = [center[0] - point[0], center[1] - point[1]];
+
+    // solve |offset + motion * t| = radius for t
+    let a = dot(motion, motion);
+    let b = dot(offset, motion);
+    let c = dot(offset, offset) - radius * radius;
+
+    // no movement, or moving away from the point
+    if a <= std::f32::EPSILON || b >= 0.0 {
+        return None;
+    }
+
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let time = (-b - discriminant.sqrt()) / a;
+    if time < 0.0 || time > 1.0 {
+        return None;
+    }
+
+    let normal = [
+        (offset[0] + motion[0] * time) / radius,
+        (offset[1] + motion[1] * time) / radius,
+    ];
+    Some(Hit { time, normal })
+}
+
+/// A Contact describes two overlapping circles. The normal points from the first to the second
+/// circle and the depth is the distance by which they overlap.
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub normal: [f32; 2],
+    pub depth: f32,
+}
+
+/// Returns the contact between two circles, if they overlap. Circles at the very same position
+/// are separated vertically.
+pub fn circle_contact(
+    center_a: [f32; 2],
+    radius_a: f32,
+    center_b: [f32; 2],
+    radius_b: f32,
+) -> Option<Contact> {
+    let offset = [center_b[0] - center_a[0], center_b[1] - center_a[1]];
+    let distance = dot(offset, offset).sqrt();
+    let depth = radius_a + radius_b - distance;
+    if depth <= 0.0 {
+        return None;
+    }
+
+    let normal = if distance <= std::f32::EPSILON {
+        [0.0, 1.0]
+    } else {
+        [offset[0] / distance, offset[1] / distance]
+    };
+    Some(Contact { normal, depth })
+}
+
+/// Returns the velocities of two bodies after an elastic collision along the contact normal,
+/// which points from the first to the second body. Bodies that are already moving apart keep
+/// their velocities.
+pub fn elastic_collision(
+    velocity_a: [f32; 2],
+    mass_a: f32,
+    velocity_b: [f32; 2],
+    mass_b: f32,
+    normal: [f32; 2],
+) -> ([f32; 2], [f32; 2]) {
+    let approach = dot([velocity_a[0] - velocity_b[0], velocity_a[1] - velocity_b[1]], normal);
+    if approach <= 0.0 {
+        return (velocity_a, velocity_b);
+    }
+
+    let impulse = 2.0 * approach / (mass_a + mass_b);
+    (
+        [
+            velocity_a[0] - impulse * mass_b * normal[0],
+            velocity_a[1] - impulse * mass_b * normal[1],
+        ],
+        [
+            velocity_b[0] + impulse * mass_a * normal[0],
+            velocity_b[1] + impulse * mass_a * normal[1],
+        ],
+    )
+}
+
+/// Reflects the velocity at a surface with the given normal.
+pub fn reflect(velocity: [f32; 2], normal: [f32; 2]) -> [f32; 2] {
+    let projection = 2.0 * dot(velocity, normal);
+    [
+        velocity[0] - projection * normal[0],
+        velocity[1] - projection * normal[1],
+    ]
+}
+
+fn dot(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+/// Returns the nearest boundary if the value lies outside of the given range.
+fn nearest_outside(value: f32, min: f32, max: f32) -> Option<f32> {
+    if value < min {
+        Some(min)
+    } else if value > max {
+        Some(max)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A paddle of the default size at the left side of the arena.
+    const PADDLE_MIN: [f32; 2] = [0.0, 42.5];
+    const PADDLE_MAX: [f32; 2] = [2.5, 57.5];
+
+    const BALL_RADIUS: f32 = 2.5;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn head_on_hit_at_the_front_of_the_paddle() {
+        let hit = sweep_circle_aabb([10.0, 50.0], BALL_RADIUS, [-10.0, 0.0], PADDLE_MIN, PADDLE_MAX)
+            .expect("ball missed the paddle");
+
+        // the ball touches the paddle once its centre is a radius away from the front
+        assert_close(hit.time, 0.5);
+        assert_eq!(hit.normal, [1.0, 0.0]);
+    }
+
+    #[test]
+    fn corner_hit_uses_the_rounded_corner() {
+        let hit = sweep_circle_aabb([5.5, 60.5], 1.0, [-5.0, -5.0], PADDLE_MIN, PADDLE_MAX)
+            .expect("ball missed the corner");
+
+        // the square corner of the inflated box would be reached at 0.4 already
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        assert_close(hit.time, (3.0 - diagonal) / 5.0);
+        assert_close(hit.normal[0], diagonal);
+        assert_close(hit.normal[1], diagonal);
+    }
+
+    #[test]
+    fn glancing_miss_next_to_the_corner() {
+        // passes the top right corner at a distance of 1.2, but enters the inflated square box
+        let offset = 1.2 * std::f32::consts::FRAC_1_SQRT_2;
+        let center = [2.5 + offset + 1.0, 57.5 + offset - 1.0];
+        assert!(sweep_circle_aabb(center, 1.0, [-2.0, 2.0], PADDLE_MIN, PADDLE_MAX).is_none());
+
+        // a radius of 1.3 reaches the corner
+        assert!(sweep_circle_aabb(center, 1.3, [-2.0, 2.0], PADDLE_MIN, PADDLE_MAX).is_some());
+    }
+
+    #[test]
+    fn overlapping_or_departing_ball_does_not_hit() {
+        // already overlapping the front of the paddle
+        let center = [3.0, 50.0];
+        assert!(sweep_circle_aabb(center, BALL_RADIUS, [-5.0, 0.0], PADDLE_MIN, PADDLE_MAX)
+            .is_none());
+
+        // moving away from the paddle
+        let center = [10.0, 50.0];
+        assert!(sweep_circle_aabb(center, BALL_RADIUS, [5.0, 0.0], PADDLE_MIN, PADDLE_MAX)
+            .is_none());
+
+        // stopping short of the paddle
+        assert!(sweep_circle_aabb(center, BALL_RADIUS, [-4.0, 0.0], PADDLE_MIN, PADDLE_MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel_through_the_paddle() {
+        // moves 100 units within the sweep, 40 times the width of the paddle
+        let motion = [-100.0, 3.0];
+        let hit = sweep_circle_aabb([50.0, 48.0], BALL_RADIUS, motion, PADDLE_MIN, PADDLE_MAX)
+            .expect("ball tunnelled through the paddle");
+
+        assert_close(hit.time, 0.45);
+        assert_eq!(hit.normal, [1.0, 0.0]);
+    }
+
+    #[test]
+    fn sweep_against_a_point() {
+        let hit = sweep_circle_point([0.0, 0.0], 1.0, [4.0, 0.0], [3.0, 0.0])
+            .expect("circle missed the point");
+        assert_close(hit.time, 0.5);
+        assert_eq!(hit.normal, [-1.0, 0.0]);
+
+        assert!(sweep_circle_point([0.0, 0.0], 1.0, [4.0, 0.0], [3.0, 1.5]).is_none());
+        assert!(sweep_circle_point([0.0, 0.0], 1.0, [-4.0, 0.0], [3.0, 0.0]).is_none());
+        assert!(sweep_circle_point([0.0, 0.0], 1.0, [0.0, 0.0], [0.5, 0.0]).is_none());
+    }
+
+    /// Returns the momentum and the kinetic energy of two bodies.
+    fn momentum_and_energy(
+        velocity_a: [f32; 2],
+        mass_a: f32,
+        velocity_b: [f32; 2],
+        mass_b: f32,
+    ) -> ([f32; 2], f32) {
+        let momentum = [
+            mass_a * velocity_a[0] + mass_b * velocity_b[0],
+            mass_a * velocity_a[1] + mass_b * velocity_b[1],
+        ];
+        let energy = 0.5 * mass_a * dot(velocity_a, velocity_a)
+            + 0.5 * mass_b * dot(velocity_b, velocity_b);
+        (momentum, energy)
+    }
+
+    fn assert_conserved(velocity_a: [f32; 2], velocity_b: [f32; 2], normal: [f32; 2]) {
+        let (after_a, after_b) = elastic_collision(velocity_a, 1.0, velocity_b, 1.0, normal);
+        let (momentum, energy) = momentum_and_energy(velocity_a, 1.0, velocity_b, 1.0);
+        let (momentum_after, energy_after) = momentum_and_energy(after_a, 1.0, after_b, 1.0);
+
+        assert_close(momentum_after[0], momentum[0]);
+        assert_close(momentum_after[1], momentum[1]);
+        assert_close(energy_after, energy);
+
+        // the bodies move apart afterwards
+        let separation = dot([after_b[0] - after_a[0], after_b[1] - after_a[1]], normal);
+        assert!(separation >= 0.0, "still approaching at {}", separation);
+    }
+
+    #[test]
+    fn equal_masses_exchange_their_velocities_head_on() {
+        let (after_a, after_b) =
+            elastic_collision([30.0, 0.0], 1.0, [-10.0, 0.0], 1.0, [1.0, 0.0]);
+        assert_close(after_a[0], -10.0);
+        assert_close(after_b[0], 30.0);
+        assert_close(after_a[1], 0.0);
+        assert_close(after_b[1], 0.0);
+
+        assert_conserved([30.0, 0.0], [-10.0, 0.0], [1.0, 0.0]);
+    }
+
+    #[test]
+    fn oblique_hit_conserves_momentum_and_energy() {
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        let normal = [diagonal, diagonal];
+        assert_conserved([40.0, 5.0], [0.0, 0.0], normal);
+        assert_conserved([25.0, -20.0], [-15.0, -35.0], normal);
+
+        // only the velocity along the normal is exchanged
+        let (after_a, after_b) = elastic_collision([40.0, 0.0], 1.0, [0.0, 0.0], 1.0, normal);
+        assert_close(after_a[0], 20.0);
+        assert_close(after_a[1], -20.0);
+        assert_close(after_b[0], 20.0);
+        assert_close(after_b[1], 20.0);
+    }
+
+    #[test]
+    fn separating_bodies_keep_their_velocities() {
+        let (after_a, after_b) =
+            elastic_collision([-10.0, 3.0], 1.0, [10.0, 3.0], 1.0, [1.0, 0.0]);
+        assert_eq!(after_a, [-10.0, 3.0]);
+        assert_eq!(after_b, [10.0, 3.0]);
+
+        // moving sideways doesn't approach either
+        let (after_a, after_b) = elastic_collision([0.0, 5.0], 1.0, [0.0, -5.0], 1.0, [1.0, 0.0]);
+        assert_eq!(after_a, [0.0, 5.0]);
+        assert_eq!(after_b, [0.0, -5.0]);
+    }
+
+    #[test]
+    fn contact_of_overlapping_circles() {
+        let contact = circle_contact([0.0, 0.0], 3.0, [3.0, 4.0], 3.0).expect("no contact");
+        assert_close(contact.normal[0], 0.6);
+        assert_close(contact.normal[1], 0.8);
+        assert_close(contact.depth, 1.0);
+
+        let contact = circle_contact([0.0, 0.0], 2.5, [3.0, 0.0], 2.5).expect("no contact");
+        assert_eq!(contact.normal, [1.0, 0.0]);
+        assert_close(contact.depth, 2.0);
+
+        // touching circles don't overlap
+        assert!(circle_contact([0.0, 0.0], 2.5, [5.0, 0.0], 2.5).is_none());
+        assert!(circle_contact([0.0, 0.0], 2.5, [6.0, 0.0], 2.5).is_none());
+    }
+
+    #[test]
+    fn coincident_circles_are_separated_vertically() {
+        let contact = circle_contact([10.0, 20.0], 2.5, [10.0, 20.0], 1.5).expect("no contact");
+        assert_eq!(contact.normal, [0.0, 1.0]);
+        assert_close(contact.depth, 4.0);
+
+        // the first body moving up into the second one bounces off
+        let (after_a, after_b) =
+            elastic_collision([0.0, 20.0], 1.0, [0.0, 0.0], 1.0, contact.normal);
+        assert_eq!(after_a, [0.0, 0.0]);
+        assert_eq!(after_b, [0.0, 20.0]);
+    }
+}
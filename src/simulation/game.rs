@@ -0,0 +1,891 @@
+use crate::{
+    config::{
+        BallConfig,
+        GameConfig,
+        MatchConfig,
+        MultiBallConfig,
+        PaddleConfig,
+        PaddleMovement,
+        RespawnRule,
+        ServeConfig,
+        ServePosition,
+        ServeRule,
+    },
+    resources::{
+        GameEvent,
+        Player,
+        Rally,
+        ScoreBoard,
+    },
+};
+
+use super::{
+    BallBody,
+    collision::{
+        self,
+        Hit,
+    },
+    Inputs,
+    PaddleBody,
+    Serve,
+};
+
+/// The maximum amount of time a single `step` may simulate. Anything beyond is dropped, to keep
+/// the simulation from spiralling when a frame takes extremely long (e.g. while dragging the
+/// window).
+const MAX_STEP_DURATION: f32 = 0.25;
+
+/// The maximum number of collisions resolved for a single ball per tick.
+const MAX_COLLISIONS_PER_TICK: usize = 4;
+
+/// The `Simulation` owns the complete gameplay state, namely the arena, the paddles, the balls and
+/// the score. It is advanced by calling `step` and doesn't know anything about `World`s,
+/// `Transform`s or rendering, which allows running matches headless.
+///
+/// Internally the simulation advances in fixed ticks, so identical input sequences always lead to
+/// identical results, independent of the frame rate. The simulation itself is free of randomness,
+/// the seed it is created with is the seed of the match, which e.g. the ai derives its random
+/// numbers from.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    arena_width: f32,
+    arena_height: f32,
+    ball_config: BallConfig,
+    paddle_config: PaddleConfig,
+    match_config: MatchConfig,
+    multi_ball_config: MultiBallConfig,
+    serve_config: ServeConfig,
+
+    /// Duration of a single tick in seconds.
+    tick_duration: f32,
+    /// Time that was passed to `step`, but not yet simulated.
+    accumulator: f32,
+    /// Number of ticks simulated so far.
+    tick: u64,
+    seed: u64,
+
+    paddles: [PaddleBody; 2],
+    balls: Vec<BallBody>,
+    score: ScoreBoard,
+    rally: Rally,
+
+    /// The player who served last.
+    server: Player,
+    /// The player who won the match, if it is over.
+    winner: Option<Player>,
+    /// Everything that happened during the last step.
+    events: Vec<GameEvent>,
+}
+
+impl Simulation {
+    /// Create a new `Simulation` with both paddles in their starting positions and the balls
+    /// waiting to be served by player 1.
+    pub fn new(config: &GameConfig, seed: u64) -> Self {
+        let arena = &config.arena;
+        let ball = &config.ball;
+        let paddle = &config.paddle;
+        let y = arena.height / 2.0;
+
+        let mut simulation = Self {
+            arena_width: arena.width,
+            arena_height: arena.height,
+            ball_config: ball.clone(),
+            paddle_config: paddle.clone(),
+            match_config: config.match_rules.clone(),
+            multi_ball_config: config.multi_ball.clone(),
+            serve_config: config.serve.clone(),
+            tick_duration: 1.0 / config.simulation.tick_rate as f32,
+            accumulator: 0.0,
+            tick: 0,
+            seed,
+            paddles: [
+                PaddleBody {
+                    player: Player::P1,
+                    position: [paddle.width * 0.5, y],
+                    velocity: 0.0,
+                    width: paddle.width,
+                    height: paddle.height,
+                },
+                PaddleBody {
+                    player: Player::P2,
+                    position: [arena.width - paddle.width * 0.5, y],
+                    velocity: 0.0,
+                    width: paddle.width,
+                    height: paddle.height,
+                },
+            ],
+            balls: Vec::new(),
+            score: ScoreBoard::default(),
+            rally: Rally::default(),
+            server: Player::P1,
+            winner: None,
+            events: Vec::new(),
+        };
+
+        for _ in 0..config.multi_ball.balls.max(1) {
+            let serve = simulation.new_serve(Player::P1);
+            simulation.balls.push(BallBody {
+                position: [arena.width / 2.0, arena.height / 2.0],
+                velocity: [ball.velocity.x, ball.velocity.y],
+                radius: ball.radius,
+                serve: Some(serve),
+                removed: false,
+            });
+        }
+        simulation.position_serving_balls();
+        simulation
+    }
+
+    /// Advance the simulation by `dt` seconds, applying the given paddle inputs. The time is
+    /// accumulated and simulated in as many fixed ticks as fit into it, the remainder is carried
+    /// over to the next step.
+    pub fn step(&mut self, dt: f32, inputs: &Inputs) {
+        self.events.clear();
+
+        self.accumulator += dt.min(MAX_STEP_DURATION);
+        while self.accumulator >= self.tick_duration {
+            self.accumulator -= self.tick_duration;
+            self.advance_tick(inputs);
+        }
+    }
+
+    /// Advance the simulation by exactly one fixed tick, applying the given paddle inputs.
+    pub fn tick(&mut self, inputs: &Inputs) {
+        self.advance(1, inputs);
+    }
+
+    /// Advance the simulation by the given number of fixed ticks, applying the same paddle
+    /// inputs to each of them, e.g. to repeat a recorded step.
+    pub fn advance(&mut self, ticks: u64, inputs: &Inputs) {
+        self.events.clear();
+        for _ in 0..ticks {
+            self.advance_tick(inputs);
+        }
+    }
+
+    /// Returns the number of ticks simulated so far.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the seed the simulation was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the duration of a single tick in seconds.
+    pub fn tick_duration(&self) -> f32 {
+        self.tick_duration
+    }
+
+    pub fn arena_width(&self) -> f32 {
+        self.arena_width
+    }
+
+    pub fn arena_height(&self) -> f32 {
+        self.arena_height
+    }
+
+    pub fn paddle(&self, player: Player) -> &PaddleBody {
+        &self.paddles[paddle_index(player)]
+    }
+
+    pub fn balls(&self) -> &[BallBody] {
+        &self.balls
+    }
+
+    pub fn score(&self) -> &ScoreBoard {
+        &self.score
+    }
+
+    pub fn rally(&self) -> &Rally {
+        &self.rally
+    }
+
+    /// Returns the player who won the match, if it is over.
+    pub fn winner(&self) -> Option<Player> {
+        self.winner
+    }
+
+    /// Returns whether the current game is in deuce, i.e. a lead of two points is required and
+    /// both players are tied at one point away from winning or more.
+    pub fn is_deuce(&self) -> bool {
+        let threshold = self.match_config.points_to_win as i32 - 1;
+        self.match_config.win_by_two
+            && self.score.score_left >= threshold
+            && self.score.score_right >= threshold
+            && self.score.score_left == self.score.score_right
+    }
+
+    /// Returns the `GameEvent`s of the last step, in the order they happened.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    fn advance_tick(&mut self, inputs: &Inputs) {
+        // nothing moves anymore once the match is over
+        if self.winner.is_some() {
+            return;
+        }
+
+        let dt = self.tick_duration;
+
+        self.move_paddles(inputs, dt);
+        self.serve_balls(inputs);
+        self.move_balls(dt);
+        self.collide_balls();
+        self.score_goals();
+
+        self.tick += 1;
+    }
+
+    /// Move the paddles according to the requested movement and the paddle movement model,
+    /// keeping them inside the arena. A paddle that runs into the top or bottom of the arena
+    /// loses its velocity.
+    fn move_paddles(&mut self, inputs: &Inputs, dt: f32) {
+        let arena_height = self.arena_height;
+        let paddle_config = &self.paddle_config;
+
+        for paddle in self.paddles.iter_mut() {
+            let previous_y = paddle.position[1];
+            let velocity = paddle_velocity(
+                paddle_config,
+                paddle.velocity,
+                inputs.movement(paddle.player),
+                dt,
+            );
+            paddle.position[1] = (previous_y + velocity * dt)
+                .min(arena_height - paddle.height * 0.5)
+                .max(paddle.height * 0.5);
+            paddle.velocity = (paddle.position[1] - previous_y) / dt;
+        }
+    }
+
+    /// Hold the balls that are waiting for a serve in place, and launch them once the serving
+    /// player requests it or the timeout ran out. Balls served together by the same player fan
+    /// out by the spread angle.
+    fn serve_balls(&mut self, inputs: &Inputs) {
+        let launch_velocity = [self.ball_config.velocity.x.abs(), self.ball_config.velocity.y];
+        let spread = self.multi_ball_config.spread.to_radians();
+        let counts = self.serving_counts();
+        let mut launched = [0; 2];
+
+        for ball in self.balls.iter_mut() {
+            if let Some(serve) = ball.serve.as_mut() {
+                let timed_out = match serve.ticks_left {
+                    Some(0) => true,
+                    Some(ref mut ticks_left) => {
+                        *ticks_left -= 1;
+                        false
+                    }
+                    None => false,
+                };
+
+                if timed_out || inputs.launch(serve.player) {
+                    // serve towards the opponent
+                    let index = paddle_index(serve.player);
+                    let facing = self.paddles[index].facing();
+                    let angle = spread * fan_offset(launched[index], counts[index]);
+                    launched[index] += 1;
+                    let velocity = [facing * launch_velocity[0], launch_velocity[1]];
+                    ball.velocity = rotate(velocity, angle);
+                    self.events.push(GameEvent::ServeStarted { player: serve.player });
+                    ball.serve = None;
+                }
+            }
+        }
+
+        self.position_serving_balls();
+    }
+
+    /// Place the balls that are waiting for a serve at their serve position. Balls served by the
+    /// same player are lined up vertically.
+    fn position_serving_balls(&mut self) {
+        let centre = [self.arena_width / 2.0, self.arena_height / 2.0];
+        let serve_position = self.serve_config.position;
+        let counts = self.serving_counts();
+        let mut placed = [0; 2];
+
+        for ball in self.balls.iter_mut() {
+            if let Some(serve) = ball.serve {
+                let index = paddle_index(serve.player);
+                let offset = 3.0 * ball.radius * fan_offset(placed[index], counts[index]);
+                placed[index] += 1;
+
+                let [x, y] = match serve_position {
+                    ServePosition::Paddle => {
+                        let paddle = &self.paddles[paddle_index(serve.player)];
+                        [
+                            paddle.position[0]
+                                + paddle.facing() * (paddle.width * 0.5 + ball.radius),
+                            paddle.position[1],
+                        ]
+                    }
+                    ServePosition::Centre => centre,
+                };
+                ball.position = [x, y + offset];
+                ball.velocity = [0.0, 0.0];
+            }
+        }
+    }
+
+    /// Returns the number of balls waiting for a serve by each player.
+    fn serving_counts(&self) -> [usize; 2] {
+        let mut counts = [0; 2];
+        for serve in self.balls.iter().filter_map(|ball| ball.serve) {
+            counts[paddle_index(serve.player)] += 1;
+        }
+        counts
+    }
+
+    /// Returns whether the given player won the current game.
+    fn has_won_game(&self, player: Player) -> bool {
+        let points = self.score.points(player);
+        let lead = points - self.score.points(player.opponent());
+        let required_lead = if self.match_config.win_by_two { 2 } else { 1 };
+
+        points >= self.match_config.points_to_win as i32 && lead >= required_lead
+    }
+
+    /// Returns a new `Serve` for the given player, including the timeout.
+    fn new_serve(&self, player: Player) -> Serve {
+        let ticks_left = if self.serve_config.timeout > 0.0 {
+            Some((self.serve_config.timeout / self.tick_duration).round() as u64)
+        } else {
+            None
+        };
+
+        Serve { player, ticks_left }
+    }
+
+    /// Move every ball according to its speed and the time passed, bouncing off the paddles and
+    /// the top and bottom of the arena along the way. Every paddle hit extends the rally and
+    /// speeds up the ball.
+    fn move_balls(&mut self, dt: f32) {
+        let arena_height = self.arena_height;
+        let paddles = &self.paddles;
+        let ball_config = &self.ball_config;
+        let paddle_config = &self.paddle_config;
+        let events = &mut self.events;
+
+        for ball in self.balls.iter_mut().filter(|ball| ball.in_play()) {
+            let hits = move_ball(
+                ball, paddles, ball_config, paddle_config, arena_height, dt, events,
+            );
+            for _ in 0..hits {
+                self.rally.add_hit();
+            }
+        }
+    }
+
+    /// Let the balls in play bounce off each other. The collisions are elastic, with the mass of a
+    /// ball growing with its area. Pairs are resolved in the order of the balls inside the
+    /// simulation, so the outcome doesn't depend on anything outside of it.
+    fn collide_balls(&mut self) {
+        let arena_height = self.arena_height;
+
+        for first in 0..self.balls.len() {
+            for second in first + 1..self.balls.len() {
+                let (head, tail) = self.balls.split_at_mut(second);
+                let (a, b) = (&mut head[first], &mut tail[0]);
+                if !a.in_play() || !b.in_play() {
+                    continue;
+                }
+
+                let contact = match collision::circle_contact(
+                    a.position, a.radius, b.position, b.radius,
+                ) {
+                    Some(contact) => contact,
+                    None => continue,
+                };
+
+                let (mass_a, mass_b) = (a.radius * a.radius, b.radius * b.radius);
+                let (velocity_a, velocity_b) = collision::elastic_collision(
+                    a.velocity, mass_a, b.velocity, mass_b, contact.normal,
+                );
+                a.velocity = velocity_a;
+                b.velocity = velocity_b;
+
+                // push the balls apart, the lighter one moves further
+                let share_a = mass_b / (mass_a + mass_b);
+                for axis in 0..2 {
+                    a.position[axis] -= contact.normal[axis] * contact.depth * share_a;
+                    b.position[axis] += contact.normal[axis] * contact.depth * (1.0 - share_a);
+                }
+                for ball in [a, b].iter_mut() {
+                    ball.position[1] = ball.position[1]
+                        .max(ball.radius)
+                        .min(arena_height - ball.radius);
+                }
+            }
+        }
+    }
+
+    /// Check whether a ball left the arena on the left or right side and award the point,
+    /// applying the match rules. The ball is then removed from play until it is served again,
+    /// according to the `RespawnRule`.
+    fn score_goals(&mut self) {
+        for index in 0..self.balls.len() {
+            let ball = &self.balls[index];
+            if !ball.in_play() {
+                continue;
+            }
+            let ball_x = ball.position[0];
+
+            let scorer = if ball_x <= ball.radius {
+                // Right player scored on the left side.
+                Some(Player::P2)
+            } else if ball_x >= self.arena_width - ball.radius {
+                // Left player scored on the right side.
+                Some(Player::P1)
+            } else {
+                None
+            };
+
+            if let Some(player) = scorer {
+                self.score.add_point(player);
+                self.rally.reset();
+                self.events.push(GameEvent::PointScored { player });
+
+                if self.has_won_game(player) {
+                    self.score.add_game(player);
+                    if self.score.games(player) >= self.match_config.games_to_win() as i32 {
+                        self.winner = Some(player);
+                        self.events.push(GameEvent::MatchWon { player });
+                        return;
+                    }
+                    self.score.reset_points();
+                }
+
+                self.server = match self.serve_config.rule {
+                    ServeRule::Alternate => self.server.opponent(),
+                    ServeRule::Loser => player.opponent(),
+                };
+                self.balls[index].removed = true;
+                self.balls[index].velocity = [0.0, 0.0];
+            }
+        }
+
+        self.respawn_balls();
+        self.position_serving_balls();
+    }
+
+    /// Let the removed balls wait for the next serve, depending on the `RespawnRule`.
+    fn respawn_balls(&mut self) {
+        let respawn = match self.multi_ball_config.respawn {
+            RespawnRule::Immediately => true,
+            RespawnRule::LastBall => self.balls.iter().all(|ball| ball.removed),
+        };
+        if !respawn {
+            return;
+        }
+
+        let serve = self.new_serve(self.server);
+        for ball in self.balls.iter_mut().filter(|ball| ball.removed) {
+            ball.removed = false;
+            ball.serve = Some(serve);
+        }
+    }
+}
+
+/// Returns the offset of the n-th of several balls that are served together, in multiples of
+/// the distance between two balls, so that the balls are centred around 0.0.
+fn fan_offset(n: usize, count: usize) -> f32 {
+    n as f32 - (count.max(1) - 1) as f32 * 0.5
+}
+
+/// Returns the vector rotated counter-clockwise by the given angle in radians.
+fn rotate(vector: [f32; 2], angle: f32) -> [f32; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [vector[0] * cos - vector[1] * sin, vector[0] * sin + vector[1] * cos]
+}
+
+/// Returns the index of the given player's paddle.
+fn paddle_index(player: Player) -> usize {
+    match player {
+        Player::P1 => 0,
+        Player::P2 => 1,
+    }
+}
+
+/// Calculates the velocity of a paddle for the next tick from its current velocity and the
+/// requested movement between -1.0 and 1.0.
+fn paddle_velocity(paddle_config: &PaddleConfig, velocity: f32, movement: f32, dt: f32) -> f32 {
+    let movement = movement.max(-1.0).min(1.0);
+    match paddle_config.movement {
+        PaddleMovement::Direct => movement * paddle_config.speed,
+        PaddleMovement::Momentum if movement == 0.0 => {
+            // slow down without overshooting into the other direction
+            let slowed = velocity.abs() - paddle_config.friction * dt;
+            slowed.max(0.0) * velocity.signum()
+        }
+        PaddleMovement::Momentum => {
+            let target = movement * paddle_config.speed;
+            let max_change = paddle_config.acceleration * dt;
+            velocity + (target - velocity).max(-max_change).min(max_change)
+        }
+    }
+}
+
+/// Moves the ball by its velocity over `dt` seconds. The movement is swept against the walls and
+/// paddles, so that fast balls can't tunnel through them: on contact the ball is placed at the
+/// exact contact point, its velocity is reflected and it continues with the remaining distance.
+///
+/// Every contact is recorded as a `GameEvent`. Returns the number of paddle hits during the
+/// movement.
+fn move_ball(
+    ball: &mut BallBody,
+    paddles: &[PaddleBody],
+    ball_config: &BallConfig,
+    paddle_config: &PaddleConfig,
+    arena_height: f32,
+    dt: f32,
+    events: &mut Vec<GameEvent>,
+) -> u32 {
+    let mut remaining = dt;
+    let mut paddle_hits = 0;
+
+    for _ in 0..MAX_COLLISIONS_PER_TICK {
+        let motion = [ball.velocity[0] * remaining, ball.velocity[1] * remaining];
+
+        // find the earliest collision along the movement, and the paddle that was hit (if any)
+        let mut first_hit = sweep_walls(ball, motion, arena_height).map(|hit| (hit, None));
+        for paddle in paddles.iter() {
+            let hit = if overlaps(ball, paddle) {
+                overlap_hit(ball, paddle)
+            } else {
+                let (min, max) = paddle.bounds();
+                collision::sweep_circle_aabb(ball.position, ball.radius, motion, min, max)
+            };
+
+            if let Some(hit) = hit {
+                if first_hit.map_or(true, |(first, _)| hit.time < first.time) {
+                    first_hit = Some((hit, Some(paddle)));
+                }
+            }
+        }
+
+        match first_hit {
+            Some((hit, paddle)) => {
+                ball.position[0] += motion[0] * hit.time;
+                ball.position[1] += motion[1] * hit.time;
+                ball.velocity = match paddle {
+                    Some(paddle) if is_front_hit(&hit, paddle) => {
+                        deflect(ball, paddle, paddle_config)
+                    }
+                    _ => collision::reflect(ball.velocity, hit.normal),
+                };
+                remaining *= 1.0 - hit.time;
+
+                // speed up the ball with every paddle hit
+                match paddle {
+                    Some(paddle) => {
+                        let speed = ball_config.speed_curve.next_speed(
+                            ball.velocity[0].hypot(ball.velocity[1]),
+                            ball_config.max_speed,
+                        );
+                        ball.velocity = with_speed(ball.velocity, speed);
+                        paddle_hits += 1;
+                        events.push(GameEvent::PaddleHit {
+                            player: paddle.player,
+                            position: ball.position,
+                            speed,
+                        });
+                    }
+                    None => events.push(GameEvent::WallBounce { position: ball.position }),
+                }
+            }
+            None => {
+                ball.position[0] += motion[0];
+                ball.position[1] += motion[1];
+                break;
+            }
+        }
+    }
+
+    paddle_hits
+}
+
+/// Returns the velocity scaled to the given speed, keeping its direction.
+fn with_speed(velocity: [f32; 2], speed: f32) -> [f32; 2] {
+    let current = velocity[0].hypot(velocity[1]);
+    if current <= std::f32::EPSILON {
+        return velocity;
+    }
+    [velocity[0] / current * speed, velocity[1] / current * speed]
+}
+
+/// Returns the collision of the ball with the top or the bottom of the arena.
+fn sweep_walls(ball: &BallBody, motion: [f32; 2], arena_height: f32) -> Option<Hit> {
+    let (wall_y, normal) = if motion[1] > 0.0 {
+        (arena_height - ball.radius, [0.0, -1.0])
+    } else if motion[1] < 0.0 {
+        (ball.radius, [0.0, 1.0])
+    } else {
+        return None;
+    };
+
+    // balls which are already beyond the wall bounce off immediately
+    let time = ((wall_y - ball.position[1]) / motion[1]).max(0.0);
+    if time <= 1.0 {
+        Some(Hit { time, normal })
+    } else {
+        None
+    }
+}
+
+/// Returns whether the ball already overlaps the paddle, e.g. because the paddle moved into it.
+fn overlaps(ball: &BallBody, paddle: &PaddleBody) -> bool {
+    let ([left, bottom], [right, top]) = paddle.bounds();
+
+    // To determine whether the ball overlaps a paddle, we create a larger rectangle around the
+    // current one, by subtracting the ball radius from the lowest coordinates, and adding the
+    // ball radius to the highest ones. The ball then overlaps the paddle if its centre is within
+    // the larger wrapper rectangle.
+    point_in_rect(
+        ball.position[0],
+        ball.position[1],
+        left - ball.radius,
+        bottom - ball.radius,
+        right + ball.radius,
+        top + ball.radius,
+    )
+}
+
+/// Returns an immediate collision with the front of the paddle if the ball overlaps it and is
+/// still moving towards the paddle's side of the arena.
+fn overlap_hit(ball: &BallBody, paddle: &PaddleBody) -> Option<Hit> {
+    if ball.velocity[0] * paddle.facing() < 0.0 {
+        Some(Hit { time: 0.0, normal: [paddle.facing(), 0.0] })
+    } else {
+        None
+    }
+}
+
+/// Returns whether the hit occurred at the front of the paddle, rather than at its top, bottom or
+/// corners.
+fn is_front_hit(hit: &Hit, paddle: &PaddleBody) -> bool {
+    hit.normal[0] * paddle.facing() > 0.0 && hit.normal[1].abs() <= std::f32::EPSILON
+}
+
+/// Calculates the velocity of a ball that was hit by the front of a paddle. The outgoing angle
+/// depends on where the ball struck the paddle: hits at the centre send it back flat, hits
+/// towards the edges send it back steeper, up to the configured maximum angle. On top of that,
+/// part of the paddle's vertical velocity is passed on to the ball. The speed of the ball is
+/// preserved.
+fn deflect(ball: &BallBody, paddle: &PaddleBody, paddle_config: &PaddleConfig) -> [f32; 2] {
+    let speed = ball.velocity[0].hypot(ball.velocity[1]);
+    let max_angle = paddle_config.max_bounce_angle.to_radians();
+
+    // relative hit position between -1.0 (bottom edge) and 1.0 (top edge)
+    let reach = paddle.height * 0.5 + ball.radius;
+    let offset = ((ball.position[1] - paddle.position[1]) / reach).max(-1.0).min(1.0);
+    let angle = offset * max_angle;
+
+    // apply english, but keep the angle within the allowed range
+    let velocity_x = speed * angle.cos();
+    let velocity_y = speed * angle.sin() + paddle_config.english * paddle.velocity;
+    let angle = velocity_y.atan2(velocity_x).max(-max_angle).min(max_angle);
+
+    [paddle.facing() * speed * angle.cos(), speed * angle.sin()]
+}
+
+// A point is in a box when its coordinates are smaller or equal than the top
+// right and larger or equal than the bottom left.
+fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
+    x >= left && x <= right && y >= bottom && y <= top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::GameConfig;
+
+    /// Upper bound of ticks for a single match, so a broken simulation fails instead of hanging.
+    const MAX_MATCH_TICKS: u64 = 2_000_000;
+
+    /// Inputs of scripted players that serve right away and follow the first ball. Every
+    /// `period` seconds one of them starts aiming too far off to reach it, so that both players
+    /// score every now and then.
+    fn follow_ball(simulation: &Simulation, period: u64) -> Inputs {
+        let seconds = simulation.current_tick() / 120;
+        let missing = if (seconds / period) % 2 == 0 { Player::P1 } else { Player::P2 };
+        let ball = &simulation.balls()[0];
+
+        let mut inputs = Inputs::default();
+        for &player in [Player::P1, Player::P2].iter() {
+            let paddle = simulation.paddle(player);
+            let error = if player == missing { paddle.height } else { 0.0 };
+            let movement = (ball.position[1] + error - paddle.position[1]) / paddle.height;
+            inputs.set_movement(player, movement.max(-1.0).min(1.0));
+            inputs.set_launch(player);
+        }
+        inputs
+    }
+
+    /// Plays a complete match tick by tick, comparing the score with the one expected from the
+    /// scored points after every tick.
+    fn play_match(config: &GameConfig, period: u64) -> Simulation {
+        let rules = &config.match_rules;
+        let mut simulation = Simulation::new(config, period);
+        let mut expected = ScoreBoard::default();
+
+        while simulation.winner().is_none() {
+            assert!(simulation.current_tick() < MAX_MATCH_TICKS, "match didn't end");
+
+            let inputs = follow_ball(&simulation, period);
+            simulation.tick(&inputs);
+
+            for event in simulation.events() {
+                if let GameEvent::PointScored { player } = *event {
+                    expected.add_point(player);
+                    let lead = expected.points(player) - expected.points(player.opponent());
+                    let required_lead = if rules.win_by_two { 2 } else { 1 };
+                    if expected.points(player) >= rules.points_to_win as i32
+                        && lead >= required_lead {
+                        expected.add_game(player);
+                        if expected.games(player) < rules.games_to_win() as i32 {
+                            expected.reset_points();
+                        }
+                    }
+                }
+            }
+            for &player in [Player::P1, Player::P2].iter() {
+                assert_eq!(simulation.score().points(player), expected.points(player));
+                assert_eq!(simulation.score().games(player), expected.games(player));
+            }
+
+            let threshold = rules.points_to_win as i32 - 1;
+            let deuce = rules.win_by_two
+                && expected.score_left >= threshold
+                && expected.score_left == expected.score_right;
+            assert_eq!(simulation.is_deuce(), deuce && simulation.winner().is_none());
+            for ball in simulation.balls() {
+                assert!(ball.position[1] >= 0.0 && ball.position[1] <= simulation.arena_height());
+            }
+        }
+        simulation
+    }
+
+    /// Checks that the final score of the match is consistent with its winner and the rules.
+    fn assert_finished(simulation: &Simulation, config: &GameConfig) {
+        let rules = &config.match_rules;
+        let winner = simulation.winner().expect("match has no winner");
+        let loser = winner.opponent();
+        let score = simulation.score();
+        let lead = score.points(winner) - score.points(loser);
+
+        assert_eq!(score.games(winner), rules.games_to_win() as i32);
+        assert!(score.games(loser) < rules.games_to_win() as i32);
+        if rules.win_by_two {
+            // either won without deuce, or by exactly two points after it
+            assert!(lead >= 2);
+            assert!(score.points(winner) == rules.points_to_win as i32 || lead == 2);
+        } else {
+            assert_eq!(score.points(winner), rules.points_to_win as i32);
+            assert!(lead >= 1);
+        }
+    }
+
+    #[test]
+    fn matches_end_with_a_consistent_score() {
+        let config = GameConfig::default();
+        for period in 1..=20 {
+            let mut simulation = play_match(&config, period);
+            assert_finished(&simulation, &config);
+
+            // nothing changes anymore once the match is over
+            let tick = simulation.current_tick();
+            let score = simulation.score().clone();
+            for _ in 0..100 {
+                simulation.tick(&Inputs::default());
+            }
+            assert_eq!(simulation.current_tick(), tick);
+            assert_eq!(simulation.score().score_left, score.score_left);
+            assert_eq!(simulation.score().score_right, score.score_right);
+        }
+    }
+
+    #[test]
+    fn short_matches_end_with_a_consistent_score() {
+        let mut config = GameConfig::default();
+        config.match_rules.points_to_win = 3;
+        config.match_rules.win_by_two = false;
+        config.match_rules.best_of = 5;
+        for period in 1..=20 {
+            let simulation = play_match(&config, period);
+            assert_finished(&simulation, &config);
+        }
+    }
+
+    #[test]
+    fn results_do_not_depend_on_the_frame_rate() {
+        let config = GameConfig::default();
+        let mut slow = Simulation::new(&config, 7);
+        let mut fast = Simulation::new(&config, 7);
+
+        // Start half a tick in, so that the frames of both simulations end together in the middle
+        // of a tick every 1/6th of a second. The inputs only change there, which gives both
+        // simulations the very same inputs for every tick.
+        let offset = slow.tick_duration() * 0.5;
+        slow.step(offset, &Inputs::default());
+        fast.step(offset, &Inputs::default());
+
+        for i in 0..3600 {
+            let mut inputs = Inputs::default();
+            inputs.set_movement(Player::P1, ((i % 7) as f32 - 3.0) / 3.0);
+            inputs.set_movement(Player::P2, ((i % 5) as f32 - 2.0) / 2.0);
+            inputs.p1_launch = i % 3 == 0;
+            inputs.p2_launch = i % 4 == 0;
+
+            for _ in 0..5 {
+                slow.step(1.0 / 30.0, &inputs);
+            }
+            for _ in 0..24 {
+                fast.step(1.0 / 144.0, &inputs);
+            }
+
+            assert_eq!(slow.current_tick(), fast.current_tick());
+            assert_eq!(slow.score().score_left, fast.score().score_left);
+            assert_eq!(slow.score().score_right, fast.score().score_right);
+            for (slow_ball, fast_ball) in slow.balls().iter().zip(fast.balls()) {
+                assert_eq!(slow_ball.position, fast_ball.position);
+                assert_eq!(slow_ball.velocity, fast_ball.velocity);
+            }
+            for &player in [Player::P1, Player::P2].iter() {
+                assert_eq!(slow.paddle(player).position, fast.paddle(player).position);
+            }
+        }
+
+        // ten minutes of play, unless the match ended early
+        assert!(slow.winner().is_some() || slow.current_tick() == 3600 * 20);
+        let score = slow.score();
+        assert!(score.score_left + score.score_right + score.games_left + score.games_right > 0);
+    }
+
+    #[test]
+    fn fast_ball_does_not_tunnel_through_a_paddle() {
+        // 25 units per tick, ten times the width of a paddle
+        let mut config = GameConfig::default();
+        config.ball.velocity.x = 3000.0;
+        config.ball.velocity.y = 0.0;
+        let mut simulation = Simulation::new(&config, 0);
+
+        let mut inputs = Inputs::default();
+        inputs.set_launch(Player::P1);
+        simulation.tick(&inputs);
+
+        let mut returned = false;
+        for _ in 0..10 {
+            simulation.tick(&Inputs::default());
+            for event in simulation.events() {
+                if let GameEvent::PointScored { .. } = *event {
+                    panic!("ball tunnelled through the paddle");
+                }
+            }
+            returned |= simulation.balls()[0].velocity[0] < 0.0;
+        }
+        assert!(returned);
+    }
+}
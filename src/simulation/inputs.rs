@@ -0,0 +1,62 @@
+use crate::resources::{
+    Command,
+    Player,
+};
+
+/// Inputs hold the paddle movement requested by each player for the next `Simulation` step, as
+/// well as whether they want to launch a ball they are serving. The movement is a value between
+/// -1.0 (down) and 1.0 (up).
+#[derive(Clone, Debug, Default)]
+pub struct Inputs {
+    pub p1_movement: f32,
+    pub p2_movement: f32,
+    pub p1_launch: bool,
+    pub p2_launch: bool,
+}
+
+impl Inputs {
+    /// Returns the requested movement of the given player.
+    pub fn movement(&self, player: Player) -> f32 {
+        match player {
+            Player::P1 => self.p1_movement,
+            Player::P2 => self.p2_movement,
+        }
+    }
+
+    /// Sets the requested movement of the given player.
+    pub fn set_movement(&mut self, player: Player, movement: f32) {
+        match player {
+            Player::P1 => self.p1_movement = movement,
+            Player::P2 => self.p2_movement = movement,
+        }
+    }
+
+    /// Returns whether the given player wants to launch the ball.
+    pub fn launch(&self, player: Player) -> bool {
+        match player {
+            Player::P1 => self.p1_launch,
+            Player::P2 => self.p2_launch,
+        }
+    }
+
+    /// Requests a launch of the ball for the given player.
+    pub fn set_launch(&mut self, player: Player) {
+        match player {
+            Player::P1 => self.p1_launch = true,
+            Player::P2 => self.p2_launch = true,
+        }
+    }
+
+    /// Applies a command the way the `MovePaddlesSystem` and the `LaunchBallSystem` do, e.g. to
+    /// apply recorded commands without going through the `CommandChannel`. Commands that don't
+    /// affect the `Simulation` are ignored.
+    pub fn apply(&mut self, command: &Command) {
+        match command {
+            Command::MovePaddle(player, movement) => {
+                self.set_movement(*player, movement.max(-1.0).min(1.0));
+            }
+            Command::LaunchBall(player) => self.set_launch(*player),
+            _ => {}
+        }
+    }
+}
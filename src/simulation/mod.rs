@@ -0,0 +1,20 @@
+pub use self::{
+    ball::{
+        BallBody,
+        Serve,
+    },
+    game::Simulation,
+    inputs::Inputs,
+    paddle::PaddleBody,
+    random::{
+        Random,
+        seed_from_time,
+    },
+};
+
+pub mod ball;
+pub mod collision;
+pub mod game;
+pub mod inputs;
+pub mod paddle;
+pub mod random;
@@ -0,0 +1,34 @@
+use crate::resources::Player;
+
+/// The PaddleBody is the simulated representation of a paddle inside the arena. The position
+/// refers to the centre of the paddle, the velocity is the vertical velocity in units per second
+/// during the last tick.
+#[derive(Clone, Debug)]
+pub struct PaddleBody {
+    pub player: Player,
+    pub position: [f32; 2],
+    pub velocity: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PaddleBody {
+    /// Returns the horizontal direction the front of the paddle is facing, i.e. 1.0 for the left
+    /// paddle and -1.0 for the right paddle.
+    pub fn facing(&self) -> f32 {
+        match self.player {
+            Player::P1 => 1.0,
+            Player::P2 => -1.0,
+        }
+    }
+
+    /// Returns the bottom left and top right corners of the paddle.
+    pub fn bounds(&self) -> ([f32; 2], [f32; 2]) {
+        let half_width = self.width * 0.5;
+        let half_height = self.height * 0.5;
+        (
+            [self.position[0] - half_width, self.position[1] - half_height],
+            [self.position[0] + half_width, self.position[1] + half_height],
+        )
+    }
+}
@@ -0,0 +1,47 @@
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+/// Random is a small xorshift pseudo random number generator. The same seed always produces the
+/// same sequence of numbers, independent of the platform.
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on a state of zero
+        Self {
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Returns a random number between 0.0 (inclusive) and 1.0 (exclusive).
+    pub fn next_f32(&mut self) -> f32 {
+        // use the upper 24 bits, which fit into the mantissa of a f32
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a random number between `min` (inclusive) and `max` (exclusive).
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+/// Returns a seed based on the current time, e.g. for a new match.
+pub fn seed_from_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() ^ u64::from(duration.subsec_nanos()))
+        .unwrap_or(0)
+}
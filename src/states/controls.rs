@@ -0,0 +1,563 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fs,
+    io,
+};
+
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{
+        is_close_requested,
+        Bindings,
+        Button,
+        InputHandler,
+    },
+    prelude::*,
+    renderer::{
+        ElementState,
+        Event,
+        KeyboardInput,
+        VirtualKeyCode,
+        WindowEvent,
+    },
+    ui::{
+        Anchor,
+        FontHandle,
+        UiText,
+        UiTransform,
+    },
+    utils::application_root_dir,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    INPUT_CONFIG_PATH,
+    USER_INPUT_CONFIG_PATH,
+};
+
+use super::menu::set_ui_text_color;
+
+/// The key bindings in the format of the input config. Unlike `Bindings`, they can be changed
+/// freely, and are converted into `Bindings` once they are complete.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct BindingsConfig {
+    axes: BTreeMap<String, AxisConfig>,
+    actions: BTreeMap<String, Vec<Vec<Button>>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+enum AxisConfig {
+    Emulated {
+        pos: Button,
+        neg: Button,
+    },
+}
+
+impl BindingsConfig {
+    fn from_bindings(bindings: &Bindings<String, String>) -> Result<Self, String> {
+        let content = ron::ser::to_string(bindings).map_err(|error| error.to_string())?;
+        ron::de::from_str(&content).map_err(|error| error.to_string())
+    }
+
+    fn to_bindings(&self) -> Result<Bindings<String, String>, String> {
+        let content = ron::ser::to_string(self).map_err(|error| error.to_string())?;
+        ron::de::from_str(&content).map_err(|error| error.to_string())
+    }
+
+    /// Returns the key bound to the given target.
+    fn key(&self, target: Target) -> Option<Button> {
+        match target {
+            Target::AxisUp(axis) => {
+                self.axes.get(axis).map(|AxisConfig::Emulated { pos, .. }| *pos)
+            }
+            Target::AxisDown(axis) => {
+                self.axes.get(axis).map(|AxisConfig::Emulated { neg, .. }| *neg)
+            }
+            Target::Actions(actions) => {
+                self.actions
+                    .get(actions[0])
+                    .and_then(|combos| combos.first())
+                    .and_then(|combo| combo.first())
+                    .cloned()
+            }
+        }
+    }
+
+    /// Returns what else the key is bound to, as the label of its `MenuItem`, or the name of the
+    /// axis or action if it can't be changed here. Every key of every axis and action is checked,
+    /// except for the ones of `target`, which are either replaced or trigger it anyway.
+    fn used_by(&self, target: Target, key: Button) -> Option<String> {
+        for (axis, AxisConfig::Emulated { pos, neg }) in self.axes.iter() {
+            for &(up, bound) in [(true, *pos), (false, *neg)].iter() {
+                if bound == key && !target.is_axis(axis, up) {
+                    return Some(label_of(|target| target.is_axis(axis, up), axis));
+                }
+            }
+        }
+
+        self.actions
+            .iter()
+            .filter(|(action, _)| !target.has_action(action))
+            .find(|(_, combos)| combos.iter().any(|combo| combo.contains(&key)))
+            .map(|(action, _)| label_of(|target| target.has_action(action), action))
+    }
+
+    /// Binds the key to the given target, replacing the previous key. Further keys of an action,
+    /// such as escape for pausing the game, are kept.
+    fn set_key(&mut self, target: Target, key: Button) {
+        match target {
+            Target::AxisUp(axis) | Target::AxisDown(axis) => {
+                let (pos, neg) = match self.axes.get(axis) {
+                    Some(AxisConfig::Emulated { pos, neg }) => (*pos, *neg),
+                    None => (key, key),
+                };
+                let (pos, neg) = match target {
+                    Target::AxisUp(_) => (key, neg),
+                    _ => (pos, key),
+                };
+                self.axes.insert(axis.to_string(), AxisConfig::Emulated { pos, neg });
+            }
+            Target::Actions(actions) => {
+                for action in actions.iter() {
+                    let combos = self.actions.entry(action.to_string()).or_insert_with(Vec::new);
+                    match combos.first_mut() {
+                        Some(combo) => *combo = vec![key],
+                        None => combos.push(vec![key]),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// What a `MenuItem` binds a key to.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Target {
+    /// The positive side of an emulated axis.
+    AxisUp(&'static str),
+    /// The negative side of an emulated axis.
+    AxisDown(&'static str),
+    /// One or more actions, which share the same key.
+    Actions(&'static [&'static str]),
+}
+
+impl Target {
+    /// Whether this is the positive (`up`) or negative side of the axis.
+    fn is_axis(&self, name: &str, up: bool) -> bool {
+        match *self {
+            Target::AxisUp(axis) => up && axis == name,
+            Target::AxisDown(axis) => !up && axis == name,
+            Target::Actions(_) => false,
+        }
+    }
+
+    /// Whether the action is one of the actions of this target.
+    fn has_action(&self, name: &str) -> bool {
+        match *self {
+            Target::Actions(actions) => actions.contains(&name),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+enum MenuItem {
+    LeftUp,
+    LeftDown,
+    LeftServe,
+    RightUp,
+    RightDown,
+    RightServe,
+    Pause,
+    QuitToMenu,
+    Reset,
+    Back,
+}
+
+impl MenuItem {
+    fn next(&self) -> Self {
+        let index = MENU_ITEMS.iter().position(|item| item == self).unwrap_or(0);
+        MENU_ITEMS[(index + 1) % MENU_ITEMS.len()]
+    }
+
+    fn previous(&self) -> Self {
+        let index = MENU_ITEMS.iter().position(|item| item == self).unwrap_or(0);
+        MENU_ITEMS[(index + MENU_ITEMS.len() - 1) % MENU_ITEMS.len()]
+    }
+
+    fn label(&self) -> &'static str {
+        match *self {
+            MenuItem::LeftUp => "Left up",
+            MenuItem::LeftDown => "Left down",
+            MenuItem::LeftServe => "Left serve",
+            MenuItem::RightUp => "Right up",
+            MenuItem::RightDown => "Right down",
+            MenuItem::RightServe => "Right serve",
+            MenuItem::Pause => "Pause",
+            MenuItem::QuitToMenu => "Quit to menu",
+            MenuItem::Reset => "Reset to defaults",
+            MenuItem::Back => "Back",
+        }
+    }
+
+    /// Returns what the menu item binds a key to, if anything.
+    fn target(&self) -> Option<Target> {
+        match *self {
+            MenuItem::LeftUp => Some(Target::AxisUp("left_paddle")),
+            MenuItem::LeftDown => Some(Target::AxisDown("left_paddle")),
+            MenuItem::LeftServe => Some(Target::Actions(&["left_launch"])),
+            MenuItem::RightUp => Some(Target::AxisUp("right_paddle")),
+            MenuItem::RightDown => Some(Target::AxisDown("right_paddle")),
+            MenuItem::RightServe => Some(Target::Actions(&["right_launch"])),
+            MenuItem::Pause => Some(Target::Actions(&["pause"])),
+            MenuItem::QuitToMenu => Some(Target::Actions(&["quit_to_menu"])),
+            MenuItem::Reset | MenuItem::Back => None,
+        }
+    }
+
+    fn text(&self, bindings: &BindingsConfig) -> String {
+        match self.target() {
+            Some(target) => {
+                let key = bindings.key(target).map_or("-".to_string(), |key| key_name(&key));
+                format!("{}: {}", self.label(), key)
+            }
+            None => self.label().to_string(),
+        }
+    }
+}
+
+/// All `MenuItem`s in the order they are displayed.
+const MENU_ITEMS: [MenuItem; 10] = [
+    MenuItem::LeftUp,
+    MenuItem::LeftDown,
+    MenuItem::LeftServe,
+    MenuItem::RightUp,
+    MenuItem::RightDown,
+    MenuItem::RightServe,
+    MenuItem::Pause,
+    MenuItem::QuitToMenu,
+    MenuItem::Reset,
+    MenuItem::Back,
+];
+
+/// Returns the label of the `MenuItem` whose target matches, or `name` if there is none.
+fn label_of(matches: impl Fn(Target) -> bool, name: &str) -> String {
+    MENU_ITEMS
+        .iter()
+        .find(|item| item.target().map_or(false, &matches))
+        .map_or_else(|| name.to_string(), |item| item.label().to_string())
+}
+
+/// The `ControlsState` lists the key bindings and lets the user change them. After selecting an
+/// item, the next key pressed is bound to it, unless the key is already in use. The changed
+/// bindings are written to the user input config, which overrides the shipped one.
+pub struct ControlsState {
+    /// The current, selected `MenuItem`.
+    current_menu_item: MenuItem,
+
+    /// The list of `MenuItem`s, holding references to the `MenuItem` `Entity`s.
+    menu_items: HashMap<MenuItem, Entity>,
+
+    /// Whether the next key pressed is bound to the current menu item.
+    waiting_for_key: bool,
+
+    /// The bindings being edited.
+    bindings: Option<BindingsConfig>,
+
+    title: Option<Entity>,
+    /// Label displaying hints and errors.
+    status: Option<Entity>,
+
+    font_handle: FontHandle,
+}
+
+impl ControlsState {
+    pub fn new(font_handle: FontHandle) -> Self {
+        Self {
+            current_menu_item: MenuItem::LeftUp,
+            menu_items: HashMap::new(),
+            waiting_for_key: false,
+            bindings: None,
+            title: None,
+            status: None,
+            font_handle,
+        }
+    }
+
+    fn select_menu_item(&mut self, world: &mut World, menu_item: MenuItem) {
+        // Set current menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [0.25, 0.25, 0.25, 1.0]);
+        }
+
+        self.current_menu_item = menu_item;
+
+        // Set new menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [1.0, 1.0, 1.0, 1.0]);
+        }
+    }
+
+    /// Execute the current menu item.
+    fn activate(&mut self, world: &mut World) -> SimpleTrans {
+        match self.current_menu_item {
+            MenuItem::Back => return Trans::Pop,
+            MenuItem::Reset => self.reset(world),
+            _ if self.bindings.is_some() => {
+                self.waiting_for_key = true;
+                self.set_status(world, "Press a key, Escape to cancel");
+            }
+            _ => {}
+        }
+
+        Trans::None
+    }
+
+    /// Bind the key to the current menu item, unless it is already in use.
+    fn bind_key(&mut self, world: &mut World, key: Button) {
+        self.waiting_for_key = false;
+
+        let target = match self.current_menu_item.target() {
+            Some(target) => target,
+            None => return,
+        };
+        let mut bindings = match self.bindings.clone() {
+            Some(bindings) => bindings,
+            None => return,
+        };
+
+        if let Some(used_by) = bindings.used_by(target, key) {
+            let status = format!("{} is already used by {}", key_name(&key), used_by);
+            self.set_status(world, &status);
+            return;
+        }
+
+        bindings.set_key(target, key);
+        match self.apply(world, &bindings) {
+            Ok(()) => {
+                let label = self.current_menu_item.label();
+                let status = format!("{} bound to {}", label, key_name(&key));
+                self.bindings = Some(bindings);
+                self.set_status(world, &status);
+            }
+            Err(error) => {
+                error!("Failed to apply key bindings: {}", error);
+                self.set_status(world, "Failed to apply key bindings");
+            }
+        }
+        self.update_texts(world);
+    }
+
+    /// Use the key bindings in the game and write them to the user input config.
+    fn apply(&self, world: &mut World, bindings: &BindingsConfig) -> Result<(), String> {
+        let input_bindings = bindings.to_bindings()?;
+        world.write_resource::<InputHandler<String, String>>().bindings = input_bindings;
+
+        let path = application_root_dir()
+            .map_err(|error| error.to_string())?
+            .join(USER_INPUT_CONFIG_PATH);
+        let content = ron::ser::to_string_pretty(bindings, Default::default())
+            .map_err(|error| error.to_string())?;
+        fs::write(path, content).map_err(|error| error.to_string())
+    }
+
+    /// Restore the shipped key bindings and remove the user input config.
+    fn reset(&mut self, world: &mut World) {
+        let result = application_root_dir()
+            .map_err(|error| error.to_string())
+            .and_then(|app_root| {
+                let bindings = Bindings::<String, String>::load_no_fallback(
+                    app_root.join(INPUT_CONFIG_PATH),
+                ).map_err(|error| error.to_string())?;
+
+                match fs::remove_file(app_root.join(USER_INPUT_CONFIG_PATH)) {
+                    Err(ref error) if error.kind() != io::ErrorKind::NotFound => {
+                        return Err(error.to_string());
+                    }
+                    _ => {}
+                }
+                Ok(bindings)
+            });
+
+        match result {
+            Ok(bindings) => {
+                self.bindings = BindingsConfig::from_bindings(&bindings).ok();
+                world.write_resource::<InputHandler<String, String>>().bindings = bindings;
+                self.set_status(world, "Restored the default key bindings");
+            }
+            Err(error) => {
+                error!("Failed to restore the default key bindings: {}", error);
+                self.set_status(world, "Failed to restore the default key bindings");
+            }
+        }
+        self.update_texts(world);
+    }
+
+    fn update_texts(&self, world: &mut World) {
+        if let Some(bindings) = self.bindings.as_ref() {
+            let mut ui_texts = world.write_storage::<UiText>();
+            for (item, entity) in self.menu_items.iter() {
+                if let Some(ui_text) = ui_texts.get_mut(*entity) {
+                    ui_text.text = item.text(bindings);
+                }
+            }
+        }
+    }
+
+    fn set_status(&self, world: &mut World, text: &str) {
+        if let Some(entity) = self.status {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(entity) {
+                ui_text.text = text.to_string();
+            }
+        }
+    }
+
+    fn create_text(
+        &self,
+        world: &mut World,
+        id: &str,
+        y: f32,
+        text: String,
+        color: [f32; 4],
+        font_size: f32,
+    ) -> Entity {
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                id.to_string(),
+                Anchor::Middle,
+                0.0, y, 2.0, 500.0, 30.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                text,
+                color,
+                font_size,
+            )).build()
+    }
+}
+
+impl SimpleState for ControlsState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        info!("ControlsState.on_start");
+        let world = data.world;
+
+        let bindings = {
+            let input = world.read_resource::<InputHandler<String, String>>();
+            BindingsConfig::from_bindings(&input.bindings)
+        };
+        let status = match bindings {
+            Ok(bindings) => {
+                self.bindings = Some(bindings);
+                "Press Return to change a key"
+            }
+            Err(error) => {
+                error!("Failed to read the key bindings: {}", error);
+                "The key bindings can't be changed"
+            }
+        };
+
+        let white = [1.0, 1.0, 1.0, 1.0];
+        let grey = [0.25, 0.25, 0.25, 1.0];
+        self.title = Some(
+            self.create_text(world, "controls", 215.0, "Controls".to_string(), white, 40.0),
+        );
+        self.status = Some(
+            self.create_text(world, "controls_status", -180.0, status.to_string(), grey, 18.0),
+        );
+
+        for (index, item) in MENU_ITEMS.iter().enumerate() {
+            let text = match self.bindings.as_ref() {
+                Some(bindings) => item.text(bindings),
+                None => item.label().to_string(),
+            };
+            let color = if *item == self.current_menu_item { white } else { grey };
+            let id = format!("controls_{}", index);
+            let y = 170.0 - 32.0 * index as f32;
+            let entity = self.create_text(world, &id, y, text, color, 22.0);
+            self.menu_items.insert(*item, entity);
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        info!("ControlsState.on_stop");
+        self.menu_items
+            .values()
+            .chain(self.title.iter())
+            .chain(self.status.iter())
+            .for_each(|&entity| {
+                let _ = data.world.delete_entity(entity);
+            })
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+
+            let key = match pressed_key(&event) {
+                Some(key) => key,
+                None => return Trans::None,
+            };
+
+            // bind the pressed key, Escape is reserved for cancelling
+            if self.waiting_for_key {
+                if key == VirtualKeyCode::Escape {
+                    self.waiting_for_key = false;
+                    self.set_status(world, "Press Return to change a key");
+                } else {
+                    self.bind_key(world, Button::Key(key));
+                }
+                return Trans::None;
+            }
+
+            match key {
+                VirtualKeyCode::Escape => return Trans::Pop,
+                VirtualKeyCode::Up | VirtualKeyCode::W => {
+                    let previous = self.current_menu_item.previous();
+                    self.select_menu_item(world, previous);
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::S => {
+                    let next = self.current_menu_item.next();
+                    self.select_menu_item(world, next);
+                }
+                VirtualKeyCode::Return => return self.activate(world),
+                _ => {}
+            }
+        }
+
+        Trans::None
+    }
+}
+
+/// Returns the key that was pressed, if any.
+fn pressed_key(event: &Event) -> Option<VirtualKeyCode> {
+    match *event {
+        Event::WindowEvent {
+            event: WindowEvent::KeyboardInput {
+                input: KeyboardInput {
+                    state: ElementState::Pressed,
+                    virtual_keycode: Some(key),
+                    ..
+                },
+                ..
+            },
+            ..
+        } => Some(key),
+        _ => None,
+    }
+}
+
+fn key_name(key: &Button) -> String {
+    match *key {
+        Button::Key(key) => format!("{:?}", key),
+        ref button => format!("{:?}", button),
+    }
+}
@@ -8,26 +8,23 @@ use amethyst::{
         transform::Transform,
     },
     ecs::prelude::*,
-    input::{
-        is_close_requested,
-        is_key_down,
-    },
+    input::is_close_requested,
     prelude::*,
     renderer::{
         Flipped,
         PosNormTex,
         SpriteRender,
         SpriteSheetHandle,
-        VirtualKeyCode,
     },
+    shrev::ReaderId,
     ui::{
-        Anchor,
         FontHandle,
         UiPrefab,
-        UiText,
-        UiTransform,
     },
-    utils::scene::BasicScenePrefab,
+    utils::{
+        application_root_dir,
+        scene::BasicScenePrefab,
+    },
 };
 
 use crate::{
@@ -36,28 +33,76 @@ use crate::{
         Paddle,
         Side,
     },
-    config::{
-        ArenaConfig,
-        BallConfig,
-        PaddleConfig,
+    config::GameConfig,
+    net::{
+        Connection,
+        NetSession,
+    },
+    replay::{
+        Replay,
+        ReplayRecorder,
+        REPLAY_DIR,
     },
     resources::{
+        Command,
+        CommandChannel,
+        MatchRequest,
+        Player,
+        PlayerControllers,
         Players,
-        ScoreText,
+        ScoreBoard,
+    },
+    simulation::{
+        seed_from_time,
+        Simulation,
+    },
+    states::{
+        game_over::GameOverState,
+        menu::{
+            is_any_key_down,
+            MenuState,
+        },
+        paused::PausedState,
     },
-    states::paused::PausedState,
     systems::GameSystemsBundle,
 };
 
 pub type GamePrefabData = BasicScenePrefab<Vec<PosNormTex>>;
 
-/// The `GameState` contains the actual game area and gameplay. If the space key is pressed during
-/// gameplay, a state transition to `PauseState` is initiated. When the escape key is pressed, the
-/// game exists.
+/// The `GameState` contains the actual game area and gameplay. If a `Command::TogglePause` is
+/// received via the `CommandChannel` (e.g. because the pause action was pressed), a state
+/// transition to `PauseState` is initiated, a `Command::QuitToMenu` returns to the `MenuState`.
+/// Once a player won the match, the `GameOverState` is pushed on top. Every match is recorded and
+/// written to a `Replay` file once it ends, or is abandoned.
+///
+/// As a demo, the `GameState` is pushed on top of the `MenuState` instead. The ai plays against
+/// itself, one match after the other, until any key is pressed.
+///
+/// During a match over the network, the `Simulation` is advanced by the `NetSession`. Such a match
+/// can't be paused or restarted, leaving it via `Command::QuitToMenu` returns to the `MenuState`,
+/// as does losing the connection.
 pub struct GameState<'a, 'b> {
     /// `State` specific dispatcher.
     dispatcher: Option<Dispatcher<'a, 'b>>,
 
+    /// Whether the match is a demo running behind the menu.
+    demo: bool,
+
+    /// Whether the match is played over the network.
+    network: bool,
+
+    /// The connection to the remote player, until the `NetSession` is created from it.
+    connection: Option<Connection>,
+
+    /// The controllers that were assigned before the demo started.
+    previous_controllers: Option<PlayerControllers>,
+
+    /// Reader for the `CommandChannel`, to receive pause and quit commands.
+    command_reader: Option<ReaderId<Command>>,
+
+    /// All `Entity`s that belong to the current match.
+    entities: Vec<Entity>,
+
     scene_handle: Handle<Prefab<GamePrefabData>>,
 
     game_ui_handle: Handle<UiPrefab>,
@@ -75,27 +120,52 @@ impl<'a, 'b> SimpleState for GameState<'a, 'b> {
         // create dispatcher
         self.create_dispatcher(world);
 
-        // initialise ui and scene
-        world.create_entity().with(self.scene_handle.clone()).build();
-        world.create_entity().with(self.game_ui_handle.clone()).build();
+        if self.demo {
+            let previous = std::mem::replace(
+                &mut *world.write_resource::<PlayerControllers>(),
+                PlayerControllers::demo(),
+            );
+            self.previous_controllers = Some(previous);
+        }
 
-        self.initialise_players(world);
-        self.initialise_ball(world);
-        self.initialise_scoreboard(world);
+        world.add_resource(MatchRequest::default());
+        self.command_reader = Some(world.write_resource::<CommandChannel>().register_reader());
+        self.start_match(world);
     }
 
-    fn on_stop(&mut self, _data: StateData<GameData>) {
+    fn on_stop(&mut self, data: StateData<GameData>) {
         info!("GameState.on_stop");
+        self.stop_match(data.world);
+
+        // closes the socket as well
+        if let Some(mut session) = data.world.res.remove::<NetSession>() {
+            session.leave();
+        }
+
+        if let Some(previous) = self.previous_controllers.take() {
+            *data.world.write_resource::<PlayerControllers>() = previous;
+        }
+    }
+
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        // discard the commands that were published while another state was on top, e.g. the
+        // command that resumed the game
+        if let Some(reader) = self.command_reader.as_mut() {
+            data.world.read_resource::<CommandChannel>().read(reader).for_each(|_| {});
+        }
     }
 
-    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
         if let StateEvent::Window(event) = &event {
-            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
+            if is_close_requested(&event) {
                 Trans::Quit
-            } else if is_key_down(&event, VirtualKeyCode::Space) {
-                Trans::Push(Box::new(PausedState::new(
-                    self.paused_ui_handle.clone(),
-                )))
+            } else if self.demo {
+                // back to the menu
+                if is_any_key_down(&event) {
+                    Trans::Pop
+                } else {
+                    Trans::None
+                }
             } else {
                 Trans::None
             }
@@ -105,10 +175,78 @@ impl<'a, 'b> SimpleState for GameState<'a, 'b> {
     }
 
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        // handle the request of a state that was popped from the stack
+        let mut request = std::mem::replace(
+            &mut *data.world.write_resource::<MatchRequest>(),
+            MatchRequest::Continue,
+        );
+
+        // handle the commands that control the flow of the game
+        let mut pause = false;
+        if let Some(reader) = self.command_reader.as_mut() {
+            for command in data.world.read_resource::<CommandChannel>().read(reader) {
+                match command {
+                    Command::TogglePause => pause = !self.demo && !self.network,
+                    Command::QuitToMenu => request = MatchRequest::MainMenu,
+                    _ => {}
+                }
+            }
+        }
+
+        // a rematch over the network would have to be agreed on by both players
+        if self.network && request == MatchRequest::Restart {
+            request = MatchRequest::MainMenu;
+        }
+
+        match request {
+            MatchRequest::Continue => {}
+            MatchRequest::Restart => {
+                self.stop_match(data.world);
+                self.start_match(data.world);
+            }
+            MatchRequest::MainMenu if self.demo => return Trans::Pop,
+            MatchRequest::MainMenu => {
+                // remove GameState from the stack and switch to MenuState
+                return Trans::Switch(Box::new(self.menu_state()));
+            }
+        }
+
+        if pause {
+            return Trans::Push(Box::new(self.paused_state()));
+        }
+
         if let Some(dispatcher) = self.dispatcher.as_mut() {
             dispatcher.dispatch(&data.world.res);
         }
 
+        // show the result once the match is over, demos simply start the next match. Over the
+        // network, only the confirmed outcome counts, a predicted one may still change.
+        let winner = if self.network {
+            let session = data.world.read_resource::<NetSession>();
+            if session.is_disconnected() {
+                return Trans::Switch(Box::new(self.menu_state()));
+            }
+            if let Some(tick) = session.desync() {
+                error!("Ending the match, it went out of sync at tick {}", tick);
+                return Trans::Switch(Box::new(self.menu_state()));
+            }
+            session.confirmed().winner()
+        } else {
+            data.world.read_resource::<Simulation>().winner()
+        };
+        if let Some(winner) = winner {
+            save_replay(data.world);
+            if self.demo {
+                self.stop_match(data.world);
+                self.start_match(data.world);
+            } else {
+                return Trans::Push(Box::new(GameOverState::new(
+                    winner,
+                    self.font_handle.clone(),
+                )));
+            }
+        }
+
         Trans::None
     }
 }
@@ -124,6 +262,12 @@ impl<'a, 'b> GameState<'a, 'b> {
 
         Self {
             dispatcher: None,
+            demo: false,
+            network: false,
+            connection: None,
+            previous_controllers: None,
+            command_reader: None,
+            entities: Vec::new(),
             scene_handle,
             game_ui_handle,
             paused_ui_handle,
@@ -132,11 +276,77 @@ impl<'a, 'b> GameState<'a, 'b> {
         }
     }
 
+    /// Create a new `GameState` running a demo, in which the ai plays against itself.
+    pub fn demo(
+        scene_handle: Handle<Prefab<GamePrefabData>>,
+        game_ui_handle: Handle<UiPrefab>,
+        paused_ui_handle: Handle<UiPrefab>,
+        sprite_sheet_handle: SpriteSheetHandle,
+        font_handle: FontHandle,
+    ) -> Self {
+        Self {
+            demo: true,
+            ..Self::new(
+                scene_handle,
+                game_ui_handle,
+                paused_ui_handle,
+                sprite_sheet_handle,
+                font_handle,
+            )
+        }
+    }
+
+    /// Create a new `GameState` running a match over the given connection to the remote player.
+    pub fn network(
+        scene_handle: Handle<Prefab<GamePrefabData>>,
+        game_ui_handle: Handle<UiPrefab>,
+        paused_ui_handle: Handle<UiPrefab>,
+        sprite_sheet_handle: SpriteSheetHandle,
+        font_handle: FontHandle,
+        connection: Connection,
+    ) -> Self {
+        Self {
+            network: true,
+            connection: Some(connection),
+            ..Self::new(
+                scene_handle,
+                game_ui_handle,
+                paused_ui_handle,
+                sprite_sheet_handle,
+                font_handle,
+            )
+        }
+    }
+
+    /// Creates the `MenuState` that is switched to when the match is left.
+    fn menu_state(&self) -> MenuState {
+        MenuState::new(
+            self.scene_handle.clone(),
+            self.game_ui_handle.clone(),
+            self.paused_ui_handle.clone(),
+            self.sprite_sheet_handle.clone(),
+            self.font_handle.clone(),
+        )
+    }
+
+    /// Creates the `PausedState` that is pushed on top when the game is paused.
+    fn paused_state(&self) -> PausedState {
+        PausedState::new(
+            self.paused_ui_handle.clone(),
+            self.font_handle.clone(),
+        )
+    }
+
     /// Creates the `State` specific `Dispatcher`.
     fn create_dispatcher(&mut self, world: &mut World) {
         if self.dispatcher.is_none() {
             let mut dispatcher_builder = DispatcherBuilder::new();
-            GameSystemsBundle::default()
+            let bundle = if self.network {
+                GameSystemsBundle::network()
+            } else {
+                GameSystemsBundle::default()
+            };
+            bundle
                 .build(&mut dispatcher_builder)
                 .expect("Failed to register GameSystemsBundle");
 
@@ -146,122 +356,173 @@ impl<'a, 'b> GameState<'a, 'b> {
         }
     }
 
-    /// Initialise the players.
-    fn initialise_players(&mut self, world: &mut World) {
-        let (arena_height, arena_width) = {
-            let config = &world.read_resource::<ArenaConfig>();
-            (config.height, config.width)
-        };
-        let (paddle_height, paddle_width) = {
-            let config = &world.read_resource::<PaddleConfig>();
-            (config.height, config.width)
-        };
+    /// Start a new match by initialising the `Simulation` and creating its `Entity`s.
+    fn start_match(&mut self, world: &mut World) {
+        self.initialise_simulation(world);
+        let entities = create_match_entities(
+            world,
+            &self.scene_handle,
+            &self.game_ui_handle,
+            &self.sprite_sheet_handle,
+        );
+        self.entities.extend(entities);
+    }
 
-        let mut left_transform = Transform::default();
-        let mut right_transform = Transform::default();
+    /// Stop the current match by saving its replay and deleting all of its `Entity`s.
+    fn stop_match(&mut self, world: &mut World) {
+        save_replay(world);
 
-        // correctly position the paddles
-        let y = arena_height / 2.0;
-        left_transform.set_translation_xyz(paddle_width * 0.5, y, 0.0);
-        right_transform.set_translation_xyz(arena_width - paddle_width * 0.5, y, 0.0);
+        if let Err(error) = world.delete_entities(&self.entities) {
+            error!("Failed to delete match entities: {:?}", error);
+        }
+        self.entities.clear();
+    }
 
-        // assign the sprites for the paddles
-        let sprite_render = SpriteRender {
-            sprite_sheet: self.sprite_sheet_handle.clone(),
-            sprite_number: 0, // paddle is the first sprite in the sprite_sheet
-        };
+    /// Initialise the `Simulation` which holds the actual gameplay state, and start recording it
+    /// unless it's a demo. Over the network, the `Simulation` is created with the seed and the
+    /// config of the host instead.
+    fn initialise_simulation(&mut self, world: &mut World) {
+        if let Some(connection) = self.connection.take() {
+            let network = world.read_resource::<GameConfig>().network.clone();
+            let simulation = Simulation::new(&connection.config, connection.seed);
+            let session = NetSession::new(
+                connection,
+                &simulation,
+                network.input_delay,
+                network.max_prediction,
+            );
+
+            world.add_resource(simulation);
+            world.add_resource(session);
+            world.add_resource(ScoreBoard::default());
+            return;
+        }
 
-        // create player 1 entity
-        let p1 = world
-            .create_entity()
-            .with(sprite_render.clone())
-            .with(Paddle {
-                side: Side::Left,
-                width: paddle_width,
-                height: paddle_height,
-            })
-            .with(left_transform)
-            .build();
+        let seed = seed_from_time();
+        let config = world.read_resource::<GameConfig>().clone();
+        if !self.demo {
+            world.write_resource::<ReplayRecorder>().start(Replay::new(config.clone(), seed));
+        }
 
-        // create player 2 entity
-        let p2 = world
-            .create_entity()
-            .with(sprite_render.clone())
-            .with(Flipped::Horizontal)
-            .with(Paddle {
-                side: Side::Right,
-                width: paddle_width,
-                height: paddle_height,
-            })
-            .with(right_transform)
-            .build();
+        world.add_resource(Simulation::new(&config, seed));
+        world.add_resource(ScoreBoard::default());
+    }
+}
 
-        world.add_resource(Players { p1, p2 });
+/// Stops recording the replay of the current match, if any, and writes it to the replay
+/// directory.
+fn save_replay(world: &mut World) {
+    // there is no recorder for matches over the network
+    let replay = world
+        .res
+        .try_fetch_mut::<ReplayRecorder>()
+        .and_then(|mut recorder| recorder.stop());
+    if let Some(mut replay) = replay {
+        replay.finish(&world.read_resource::<Simulation>());
+
+        let result = application_root_dir()
+            .map_err(|error| error.to_string())
+            .and_then(|app_root| replay.save(app_root.join(REPLAY_DIR)));
+        match result {
+            Ok(path) => info!("Saved replay to {}", path.display()),
+            Err(error) => error!("Failed to save replay: {}", error),
+        }
     }
+}
 
-    /// Initialise the ball.
-    fn initialise_ball(&mut self, world: &mut World) {
-        let (arena_width, arena_height) = {
-            let config = world.read_resource::<ArenaConfig>();
-            (config.width, config.height)
-        };
-        let (ball_velocity_x, ball_velocity_y, ball_radius) = {
-            let config = world.read_resource::<BallConfig>();
-            (config.velocity.x, config.velocity.y, config.radius)
-        };
+/// Creates the `Entity`s of a match for the current `Simulation`: the scene, the ui (including
+/// the scoreboard), the paddles and the balls.
+pub fn create_match_entities(
+    world: &mut World,
+    scene_handle: &Handle<Prefab<GamePrefabData>>,
+    game_ui_handle: &Handle<UiPrefab>,
+    sprite_sheet_handle: &SpriteSheetHandle,
+) -> Vec<Entity> {
+    let scene = world.create_entity().with(scene_handle.clone()).build();
+    let game_ui = world.create_entity().with(game_ui_handle.clone()).build();
+
+    let mut entities = vec![scene, game_ui];
+    entities.extend(create_paddles(world, sprite_sheet_handle));
+    entities.extend(create_balls(world, sprite_sheet_handle));
+    entities
+}
 
-        let mut local_transform = Transform::default();
-        local_transform.set_translation_xyz(arena_height / 2.0, arena_width / 2.0, 0.0);
+/// Creates the paddles of both players.
+fn create_paddles(world: &mut World, sprite_sheet_handle: &SpriteSheetHandle) -> Vec<Entity> {
+    let (p1_body, p2_body) = {
+        let simulation = world.read_resource::<Simulation>();
+        (simulation.paddle(Player::P1).clone(), simulation.paddle(Player::P2).clone())
+    };
+
+    // correctly position the paddles
+    let mut left_transform = Transform::default();
+    left_transform.set_translation_xyz(p1_body.position[0], p1_body.position[1], 0.0);
+    let mut right_transform = Transform::default();
+    right_transform.set_translation_xyz(p2_body.position[0], p2_body.position[1], 0.0);
+
+    // assign the sprites for the paddles
+    let sprite_render = SpriteRender {
+        sprite_sheet: sprite_sheet_handle.clone(),
+        sprite_number: 0, // paddle is the first sprite in the sprite_sheet
+    };
+
+    // create player 1 entity
+    let p1 = world
+        .create_entity()
+        .with(sprite_render.clone())
+        .with(Paddle {
+            side: Side::Left,
+            width: p1_body.width,
+            height: p1_body.height,
+        })
+        .with(left_transform)
+        .build();
+
+    // create player 2 entity
+    let p2 = world
+        .create_entity()
+        .with(sprite_render.clone())
+        .with(Flipped::Horizontal)
+        .with(Paddle {
+            side: Side::Right,
+            width: p2_body.width,
+            height: p2_body.height,
+        })
+        .with(right_transform)
+        .build();
+
+    world.add_resource(Players { p1, p2 });
+    vec![p1, p2]
+}
 
-        // assign the sprite for the ball
-        let sprite_render = SpriteRender {
-            sprite_sheet: self.sprite_sheet_handle.clone(),
-            sprite_number: 1, // ball is the second sprite in the sprite_sheet
-        };
+/// Creates an `Entity` for every ball of the `Simulation`.
+fn create_balls(world: &mut World, sprite_sheet_handle: &SpriteSheetHandle) -> Vec<Entity> {
+    let positions: Vec<[f32; 2]> = world
+        .read_resource::<Simulation>()
+        .balls()
+        .iter()
+        .map(|ball| ball.position)
+        .collect();
+
+    // assign the sprite for the ball
+    let sprite_render = SpriteRender {
+        sprite_sheet: sprite_sheet_handle.clone(),
+        sprite_number: 1, // ball is the second sprite in the sprite_sheet
+    };
+
+    // create an entity for every ball of the simulation
+    let mut entities = Vec::new();
+    for (index, [x, y]) in positions.into_iter().enumerate() {
+        let mut local_transform = Transform::default();
+        local_transform.set_translation_xyz(x, y, 0.0);
 
-        // create the ball entity
-        world
+        let ball = world
             .create_entity()
-            .with(sprite_render)
-            .with(Ball {
-                velocity: [ball_velocity_x, ball_velocity_y],
-                radius: ball_radius,
-            })
+            .with(sprite_render.clone())
+            .with(Ball { index })
             .with(local_transform)
             .build();
+        entities.push(ball);
     }
-
-    /// Initialise a ui scoreboard
-    fn initialise_scoreboard(&mut self, world: &mut World) {
-        let p1_transform = UiTransform::new(
-            "P1".to_string(), Anchor::TopMiddle,
-            -50.0, -50.0, 1.0, 200.0, 50.0,
-        );
-        let p2_transform = UiTransform::new(
-            "P2".to_string(), Anchor::TopMiddle,
-            50.0, -50.0, 1.0, 200.0, 50.0,
-        );
-
-        let p1_score = world
-            .create_entity()
-            .with(p1_transform)
-            .with(UiText::new(
-                self.font_handle.clone(),
-                "0".to_string(),
-                [1.0, 1.0, 1.0, 1.0],
-                50.0,
-            )).build();
-
-        let p2_score = world
-            .create_entity()
-            .with(p2_transform)
-            .with(UiText::new(
-                self.font_handle.clone(),
-                "0".to_string(),
-                [1.0, 1.0, 1.0, 1.0],
-                50.0,
-            )).build();
-
-        world.add_resource(ScoreText { p1_score, p2_score });
-    }
+    entities
 }
@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{
+        is_close_requested,
+        is_key_down,
+    },
+    prelude::*,
+    renderer::VirtualKeyCode,
+    shrev::ReaderId,
+    ui::{
+        Anchor,
+        FontHandle,
+        UiText,
+        UiTransform,
+    },
+};
+
+use crate::resources::{
+    Command,
+    CommandChannel,
+    MatchRequest,
+    Player,
+    ScoreBoard,
+};
+
+use super::menu::set_ui_text_color;
+
+#[derive(Eq, PartialEq, Hash)]
+enum MenuItem {
+    Rematch,
+    MainMenu,
+}
+
+impl MenuItem {
+    fn next(&self) -> Self {
+        match *self {
+            MenuItem::Rematch => MenuItem::MainMenu,
+            MenuItem::MainMenu => MenuItem::Rematch,
+        }
+    }
+
+    fn previous(&self) -> Self {
+        match *self {
+            MenuItem::Rematch => MenuItem::MainMenu,
+            MenuItem::MainMenu => MenuItem::Rematch,
+        }
+    }
+}
+
+/// The `GameOverState` is pushed on top of the `GameState` once a player won the match. It
+/// displays the winner and the final score, and lets the players choose between a rematch and
+/// returning to the `MenuState`. A `Command::QuitToMenu` received via the `CommandChannel` also
+/// returns to the `MenuState`.
+pub struct GameOverState {
+    /// The player who won the match.
+    winner: Player,
+
+    /// The current, selected `MenuItem`.
+    current_menu_item: MenuItem,
+
+    /// The list of `MenuItem`s, holding references to the `MenuItem` `Entity`s.
+    menu_items: HashMap<MenuItem, Entity>,
+
+    /// Labels displaying the winner and the final score.
+    labels: Vec<Entity>,
+
+    /// Reader for the `CommandChannel`, to receive quit commands.
+    command_reader: Option<ReaderId<Command>>,
+
+    font_handle: FontHandle,
+}
+
+impl GameOverState {
+    pub fn new(winner: Player, font_handle: FontHandle) -> Self {
+        Self {
+            winner,
+            current_menu_item: MenuItem::Rematch,
+            menu_items: HashMap::new(),
+            labels: Vec::new(),
+            command_reader: None,
+            font_handle,
+        }
+    }
+
+    fn select_next_menu_item(&mut self, world: &mut World) {
+        // Set current menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [0.25, 0.25, 0.25, 1.0]);
+        }
+
+        self.current_menu_item = self.current_menu_item.next();
+
+        // Set new menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [1.0, 1.0, 1.0, 1.0]);
+        }
+    }
+
+    fn select_previous_menu_item(&mut self, world: &mut World) {
+        // Set current menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [0.25, 0.25, 0.25, 1.0]);
+        }
+
+        self.current_menu_item = self.current_menu_item.previous();
+
+        // Set new menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [1.0, 1.0, 1.0, 1.0]);
+        }
+    }
+
+    fn create_text(
+        &self,
+        world: &mut World,
+        id: &str,
+        y: f32,
+        text: String,
+        color: [f32; 4],
+        font_size: f32,
+    ) -> Entity {
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                id.to_string(),
+                Anchor::Middle,
+                0.0, y, 2.0, 400.0, 50.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                text,
+                color,
+                font_size,
+            )).build()
+    }
+}
+
+impl SimpleState for GameOverState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        info!("GameOverState.on_start");
+        let world = data.world;
+
+        let winner = match self.winner {
+            Player::P1 => "Player 1 wins",
+            Player::P2 => "Player 2 wins",
+        };
+        let (games, points) = {
+            let score_board = world.read_resource::<ScoreBoard>();
+            (
+                format!("Games {} - {}", score_board.games_left, score_board.games_right),
+                format!("Points {} - {}", score_board.score_left, score_board.score_right),
+            )
+        };
+        info!("{} | {} | {}", winner, games, points);
+
+        // Initialise ui elements
+        let white = [1.0, 1.0, 1.0, 1.0];
+        self.labels = vec![
+            self.create_text(world, "winner", 100.0, winner.to_string(), white, 40.0),
+            self.create_text(world, "games", 55.0, games, white, 25.0),
+            self.create_text(world, "points", 25.0, points, white, 25.0),
+        ];
+
+        let rematch = self.create_text(
+            world, "rematch", -50.0, "Rematch".to_string(), white, 40.0,
+        );
+        self.menu_items.insert(MenuItem::Rematch, rematch);
+
+        let main_menu = self.create_text(
+            world, "main_menu", -100.0, "Main Menu".to_string(), [0.25, 0.25, 0.25, 1.0], 40.0,
+        );
+        self.menu_items.insert(MenuItem::MainMenu, main_menu);
+
+        self.command_reader = Some(world.write_resource::<CommandChannel>().register_reader());
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        info!("GameOverState.on_stop");
+        self.menu_items.values().chain(self.labels.iter()).for_each(|&entity| {
+            let _ = data.world.delete_entity(entity);
+        })
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        if let StateEvent::Window(event) = event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+
+            // toggle previous menu item
+            if is_key_down(&event, VirtualKeyCode::Up) || is_key_down(&event, VirtualKeyCode::W) {
+                self.select_previous_menu_item(world);
+            }
+
+            // toggle next menu item
+            if is_key_down(&event, VirtualKeyCode::Down) || is_key_down(&event, VirtualKeyCode::S) {
+                self.select_next_menu_item(world);
+            }
+
+            // execute menu item command and hand it down to the GameState
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                *world.write_resource::<MatchRequest>() = match self.current_menu_item {
+                    MenuItem::Rematch => MatchRequest::Restart,
+                    MenuItem::MainMenu => MatchRequest::MainMenu,
+                };
+                return Trans::Pop;
+            }
+        }
+
+        // event was not of type StateEvent, so no transition is required
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        let mut quit = false;
+        if let Some(reader) = self.command_reader.as_mut() {
+            for command in data.world.read_resource::<CommandChannel>().read(reader) {
+                if let Command::QuitToMenu = command {
+                    quit = true;
+                }
+            }
+        }
+
+        // leave the match
+        if quit {
+            *data.world.write_resource::<MatchRequest>() = MatchRequest::MainMenu;
+            return Trans::Pop;
+        }
+        Trans::None
+    }
+}
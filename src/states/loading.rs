@@ -33,17 +33,23 @@ use amethyst::{
     },
 };
 
+use crate::replay::Replay;
+
 use super::{
     game::GamePrefabData,
     menu::MenuState,
+    replay::ReplayState,
 };
 
 /// The `LoadingState` loads all required `Assets` and ensures everything is ready before
-/// transitioning into the `GameState`.
+/// transitioning into the `MenuState`, or the `ReplayState` if a replay should be played back.
 #[derive(Default)]
 pub struct LoadingState {
     progress: ProgressCounter,
 
+    /// The replay to play back once loading completed.
+    replay: Option<Replay>,
+
     loading_ui: Option<Entity>,
     scene_handle: Option<Handle<Prefab<GamePrefabData>>>,
 
@@ -121,7 +127,17 @@ impl SimpleState for LoadingState {
                     let _ = data.world.delete_entity(entity);
                 }
 
-                // remove LoadingState from the stack and switch to MenuState
+                // remove LoadingState from the stack and switch to ReplayState or MenuState
+                if let Some(replay) = self.replay.take() {
+                    return Trans::Switch(Box::new(ReplayState::new(
+                        replay,
+                        self.scene_handle.take().unwrap(),
+                        self.game_ui_handle.take().unwrap(),
+                        self.paused_ui_handle.take().unwrap(),
+                        self.sprite_sheet_handle.take().unwrap(),
+                        self.font_handle.take().unwrap(),
+                    )));
+                }
                 Trans::Switch(Box::new(MenuState::new(
                     self.scene_handle.take().unwrap(),
                     self.game_ui_handle.take().unwrap(),
@@ -141,6 +157,14 @@ impl SimpleState for LoadingState {
 }
 
 impl LoadingState {
+    /// Create a `LoadingState` that plays the given replay back once loading completed.
+    pub fn with_replay(replay: Replay) -> Self {
+        Self {
+            replay: Some(replay),
+            ..Self::default()
+        }
+    }
+
     /// Load the sprite sheet.
     fn load_sprite_sheet(&mut self, world: &mut World) -> SpriteSheetHandle {
         // Load the sprite sheet necessary to render the graphics.
@@ -0,0 +1,214 @@
+use amethyst::{
+    assets::{
+        Handle,
+        Prefab,
+    },
+    core::Time,
+    ecs::prelude::Entity,
+    input::{
+        is_close_requested,
+        is_key_down,
+    },
+    prelude::*,
+    renderer::{
+        SpriteSheetHandle,
+        VirtualKeyCode,
+    },
+    ui::{
+        Anchor,
+        FontHandle,
+        UiPrefab,
+        UiText,
+        UiTransform,
+    },
+};
+
+use crate::{
+    config::GameConfig,
+    net::{
+        with_conditions,
+        Lobby,
+        UdpTransport,
+    },
+    resources::PlayerControllers,
+    simulation::seed_from_time,
+};
+
+use super::{
+    game::{
+        GamePrefabData,
+        GameState,
+    },
+    menu::MenuState,
+};
+
+/// The `LobbyState` is shown while the host waits for a player to join, or while joining the host
+/// configured in the `NetworkConfig`. Once both found each other, it switches to the `GameState`
+/// running the match over the network. Pressing escape returns to the `MenuState`.
+pub struct LobbyState {
+    /// Whether this instance hosts the match.
+    host: bool,
+
+    /// The lobby, until the match starts or it failed.
+    lobby: Option<Lobby>,
+
+    /// Label displaying what the lobby is waiting for.
+    label: Option<Entity>,
+
+    /// `Asset` handles.
+    scene_handle: Handle<Prefab<GamePrefabData>>,
+    game_ui_handle: Handle<UiPrefab>,
+    paused_ui_handle: Handle<UiPrefab>,
+    sprite_sheet_handle: SpriteSheetHandle,
+    font_handle: FontHandle,
+}
+
+impl LobbyState {
+    /// Create a `LobbyState` that either hosts a match, or joins one.
+    pub fn new(
+        host: bool,
+        scene_handle: Handle<Prefab<GamePrefabData>>,
+        game_ui_handle: Handle<UiPrefab>,
+        paused_ui_handle: Handle<UiPrefab>,
+        sprite_sheet_handle: SpriteSheetHandle,
+        font_handle: FontHandle,
+    ) -> Self {
+        Self {
+            host,
+            lobby: None,
+            label: None,
+            scene_handle,
+            game_ui_handle,
+            paused_ui_handle,
+            sprite_sheet_handle,
+            font_handle,
+        }
+    }
+
+    /// Opens the socket and creates the `Lobby`, returning the text to display.
+    fn open(&mut self, config: &GameConfig) -> String {
+        let network = &config.network;
+        let result = if self.host {
+            UdpTransport::host(network.port)
+                .map_err(|error| error.to_string())
+                .and_then(|transport| {
+                    let transport = with_conditions(transport, network);
+                    Lobby::host(transport, config.clone(), seed_from_time())
+                })
+        } else {
+            UdpTransport::join(&network.address)
+                .map(|transport| Lobby::join(with_conditions(transport, network)))
+                .map_err(|error| error.to_string())
+        };
+
+        match result {
+            Ok(lobby) => {
+                self.lobby = Some(lobby);
+                if self.host {
+                    format!("Waiting for a player on port {}", network.port)
+                } else {
+                    format!("Joining {}", network.address)
+                }
+            }
+            Err(error) => {
+                error!("Failed to open the lobby: {}", error);
+                format!("Failed: {}", error)
+            }
+        }
+    }
+
+    fn set_text(&self, world: &mut World, text: String) {
+        if let Some(entity) = self.label {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(entity) {
+                ui_text.text = text;
+            }
+        }
+    }
+}
+
+impl SimpleState for LobbyState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        info!("LobbyState.on_start");
+        let world = data.world;
+
+        let config = world.read_resource::<GameConfig>().clone();
+        let text = self.open(&config);
+
+        // Initialise ui elements
+        self.label = Some(world
+            .create_entity()
+            .with(UiTransform::new(
+                "lobby".to_string(),
+                Anchor::Middle,
+                0.0, 0.0, 1.0, 800.0, 50.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                text,
+                [1.0, 1.0, 1.0, 1.0],
+                25.0,
+            )).build(),
+        );
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        info!("LobbyState.on_stop");
+        if let Some(entity) = self.label.take() {
+            let _ = data.world.delete_entity(entity);
+        }
+    }
+
+    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        if let StateEvent::Window(event) = event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+
+            // back to the menu
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Switch(Box::new(MenuState::new(
+                    self.scene_handle.clone(),
+                    self.game_ui_handle.clone(),
+                    self.paused_ui_handle.clone(),
+                    self.sprite_sheet_handle.clone(),
+                    self.font_handle.clone(),
+                )));
+            }
+        }
+
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        let result = match self.lobby.as_mut() {
+            Some(lobby) => lobby.update(data.world.read_resource::<Time>().delta_seconds()),
+            None => return Trans::None,
+        };
+
+        match result {
+            Ok(Some(connection)) => {
+                info!("Starting the match as {:?}", connection.local);
+                let mouse_player = data.world.read_resource::<GameConfig>().mouse.player;
+                *data.world.write_resource::<PlayerControllers>() =
+                    PlayerControllers::network(connection.local).with_mouse(mouse_player);
+
+                // remove LobbyState from the stack and switch to GameState
+                Trans::Switch(Box::new(GameState::network(
+                    self.scene_handle.clone(),
+                    self.game_ui_handle.clone(),
+                    self.paused_ui_handle.clone(),
+                    self.sprite_sheet_handle.clone(),
+                    self.font_handle.clone(),
+                    connection,
+                )))
+            }
+            Ok(None) => Trans::None,
+            Err(error) => {
+                error!("Failed to start the match: {}", error);
+                self.lobby = None;
+                self.set_text(data.world, format!("Failed: {}", error));
+                Trans::None
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use amethyst::{
         Handle,
         Prefab,
     },
+    core::Time,
     ecs::prelude::Entity,
     input::{
         is_close_requested,
@@ -12,8 +13,12 @@ use amethyst::{
     },
     prelude::*,
     renderer::{
+        ElementState,
+        Event,
+        KeyboardInput,
         SpriteSheetHandle,
         VirtualKeyCode,
+        WindowEvent,
     },
     ui::{
         Anchor,
@@ -24,25 +29,48 @@ use amethyst::{
     },
 };
 
-use crate::resources::GameMode;
+use crate::{
+    config::GameConfig,
+    resources::{
+        Player,
+        PlayerControllers,
+        Script,
+    },
+};
 
-use super::game::{
-    GamePrefabData,
-    GameState,
+use super::{
+    controls::ControlsState,
+    game::{
+        GamePrefabData,
+        GameState,
+    },
+    lobby::LobbyState,
 };
 
 #[derive(Eq, PartialEq, Hash)]
 enum MenuItem {
     SinglePlayer,
+    Difficulty,
+    Side,
     MultiPlayer,
+    Host,
+    Join,
+    Mouse,
+    Controls,
     Quit,
 }
 
 impl MenuItem {
     fn next(&self) -> Self {
         match *self {
-            MenuItem::SinglePlayer => MenuItem::MultiPlayer,
-            MenuItem::MultiPlayer => MenuItem::Quit,
+            MenuItem::SinglePlayer => MenuItem::Difficulty,
+            MenuItem::Difficulty => MenuItem::Side,
+            MenuItem::Side => MenuItem::MultiPlayer,
+            MenuItem::MultiPlayer => MenuItem::Host,
+            MenuItem::Host => MenuItem::Join,
+            MenuItem::Join => MenuItem::Mouse,
+            MenuItem::Mouse => MenuItem::Controls,
+            MenuItem::Controls => MenuItem::Quit,
             MenuItem::Quit => MenuItem::SinglePlayer
         }
     }
@@ -50,8 +78,14 @@ impl MenuItem {
     fn previous(&self) -> Self {
         match *self {
             MenuItem::SinglePlayer => MenuItem::Quit,
-            MenuItem::MultiPlayer => MenuItem::SinglePlayer,
-            MenuItem::Quit => MenuItem::MultiPlayer
+            MenuItem::Difficulty => MenuItem::SinglePlayer,
+            MenuItem::Side => MenuItem::Difficulty,
+            MenuItem::MultiPlayer => MenuItem::Side,
+            MenuItem::Host => MenuItem::MultiPlayer,
+            MenuItem::Join => MenuItem::Host,
+            MenuItem::Mouse => MenuItem::Join,
+            MenuItem::Controls => MenuItem::Mouse,
+            MenuItem::Quit => MenuItem::Controls
         }
     }
 }
@@ -63,6 +97,9 @@ pub struct MenuState {
     /// The list of `MenuItem`s, holding references to the `MenuItem` `Entity`s.
     menu_items: HashMap<MenuItem, Entity>,
 
+    /// Seconds since the last key press, used to start the demo.
+    idle_time: f32,
+
     /// `Asset` handles.
     scene_handle: Handle<Prefab<GamePrefabData>>,
     game_ui_handle: Handle<UiPrefab>,
@@ -83,6 +120,7 @@ impl MenuState {
         Self {
             current_menu_item: MenuItem::SinglePlayer,
             menu_items: HashMap::new(),
+            idle_time: 0.0,
             scene_handle,
             game_ui_handle,
             paused_ui_handle,
@@ -118,6 +156,74 @@ impl MenuState {
             set_ui_text_color(world, entity, [1.0, 1.0, 1.0, 1.0]);
         }
     }
+
+    /// Shows or hides the menu items, e.g. to make room for the controls.
+    fn set_menu_visible(&self, world: &mut World, visible: bool) {
+        for (item, entity) in self.menu_items.iter() {
+            let color = if !visible {
+                [0.0, 0.0, 0.0, 0.0]
+            } else if *item == self.current_menu_item {
+                [1.0, 1.0, 1.0, 1.0]
+            } else {
+                [0.25, 0.25, 0.25, 1.0]
+            };
+            set_ui_text_color(world, entity, color);
+        }
+    }
+
+    /// Changes the ai difficulty in the `GameConfig` and updates the difficulty menu item.
+    fn change_difficulty(&mut self, world: &mut World, next: bool) {
+        let text = {
+            let mut config = world.write_resource::<GameConfig>();
+            config.ai.difficulty = if next {
+                config.ai.difficulty.next()
+            } else {
+                config.ai.difficulty.previous()
+            };
+            difficulty_text(&config)
+        };
+
+        if let Some(entity) = self.menu_items.get(&MenuItem::Difficulty) {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(*entity) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    /// Changes the side of the human in single player matches in the `GameConfig` and updates
+    /// the side menu item.
+    fn change_side(&mut self, world: &mut World) {
+        let text = {
+            let mut config = world.write_resource::<GameConfig>();
+            config.ai.human = config.ai.human.opponent();
+            side_text(&config)
+        };
+
+        if let Some(entity) = self.menu_items.get(&MenuItem::Side) {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(*entity) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    /// Changes the player using the mouse in the `GameConfig` and updates the mouse menu item.
+    fn change_mouse_player(&mut self, world: &mut World, next: bool) {
+        let text = {
+            let mut config = world.write_resource::<GameConfig>();
+            if next {
+                config.mouse.next();
+            } else {
+                config.mouse.previous();
+            }
+            mouse_text(&config)
+        };
+
+        if let Some(entity) = self.menu_items.get(&MenuItem::Mouse) {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(*entity) {
+                ui_text.text = text;
+            }
+        }
+    }
 }
 
 impl SimpleState for MenuState {
@@ -131,7 +237,7 @@ impl SimpleState for MenuState {
             .with(UiTransform::new(
                 "sp".to_string(),
                 Anchor::Middle,
-                0.0, 50.0, 1.0, 200.0, 50.0,
+                0.0, 140.0, 1.0, 200.0, 50.0,
             ))
             .with(UiText::new(
                 self.font_handle.clone(),
@@ -141,12 +247,44 @@ impl SimpleState for MenuState {
             )).build(),
         );
 
+        let difficulty = difficulty_text(&world.read_resource::<GameConfig>());
+        self.menu_items.insert(MenuItem::Difficulty, world
+            .create_entity()
+            .with(UiTransform::new(
+                "difficulty".to_string(),
+                Anchor::Middle,
+                0.0, 100.0, 1.0, 400.0, 40.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                difficulty,
+                [0.25, 0.25, 0.25, 1.0],
+                25.0,
+            )).build(),
+        );
+
+        let side = side_text(&world.read_resource::<GameConfig>());
+        self.menu_items.insert(MenuItem::Side, world
+            .create_entity()
+            .with(UiTransform::new(
+                "side".to_string(),
+                Anchor::Middle,
+                0.0, 65.0, 1.0, 400.0, 40.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                side,
+                [0.25, 0.25, 0.25, 1.0],
+                25.0,
+            )).build(),
+        );
+
         self.menu_items.insert(MenuItem::MultiPlayer, world
             .create_entity()
             .with(UiTransform::new(
                 "mp".to_string(),
                 Anchor::Middle,
-                0.0, 0.0, 1.0, 200.0, 50.0,
+                0.0, 25.0, 1.0, 200.0, 50.0,
             ))
             .with(UiText::new(
                 self.font_handle.clone(),
@@ -156,12 +294,80 @@ impl SimpleState for MenuState {
             )).build(),
         );
 
+        let (host, join) = {
+            let config = world.read_resource::<GameConfig>();
+            (
+                format!("Host LAN Game: Port {}", config.network.port),
+                format!("Join LAN Game: {}", config.network.address),
+            )
+        };
+        self.menu_items.insert(MenuItem::Host, world
+            .create_entity()
+            .with(UiTransform::new(
+                "host".to_string(),
+                Anchor::Middle,
+                0.0, -15.0, 1.0, 400.0, 40.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                host,
+                [0.25, 0.25, 0.25, 1.0],
+                25.0,
+            )).build(),
+        );
+
+        self.menu_items.insert(MenuItem::Join, world
+            .create_entity()
+            .with(UiTransform::new(
+                "join".to_string(),
+                Anchor::Middle,
+                0.0, -50.0, 1.0, 400.0, 40.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                join,
+                [0.25, 0.25, 0.25, 1.0],
+                25.0,
+            )).build(),
+        );
+
+        let mouse = mouse_text(&world.read_resource::<GameConfig>());
+        self.menu_items.insert(MenuItem::Mouse, world
+            .create_entity()
+            .with(UiTransform::new(
+                "mouse".to_string(),
+                Anchor::Middle,
+                0.0, -85.0, 1.0, 400.0, 40.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                mouse,
+                [0.25, 0.25, 0.25, 1.0],
+                25.0,
+            )).build(),
+        );
+
+        self.menu_items.insert(MenuItem::Controls, world
+            .create_entity()
+            .with(UiTransform::new(
+                "controls".to_string(),
+                Anchor::Middle,
+                0.0, -130.0, 1.0, 200.0, 50.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                "Controls".to_string(),
+                [0.25, 0.25, 0.25, 1.0],
+                40.0,
+            )).build(),
+        );
+
         self.menu_items.insert(MenuItem::Quit, world
             .create_entity()
             .with(UiTransform::new(
-                "sp".to_string(),
+                "quit".to_string(),
                 Anchor::Middle,
-                0.0, -50.0, 1.0, 200.0, 50.0,
+                0.0, -180.0, 1.0, 200.0, 50.0,
             ))
             .with(UiText::new(
                 self.font_handle.clone(),
@@ -171,8 +377,8 @@ impl SimpleState for MenuState {
             )).build(),
         );
 
-        // register GameMode resource
-        world.res.insert(GameMode::MultiPlayer);
+        // register PlayerControllers resource
+        world.res.insert(PlayerControllers::multi_player());
     }
 
     fn on_stop(&mut self, data: StateData<GameData>) {
@@ -182,6 +388,12 @@ impl SimpleState for MenuState {
         })
     }
 
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        // the demo or the controls were left
+        self.idle_time = 0.0;
+        self.set_menu_visible(data.world, true);
+    }
+
     fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
         let world = data.world;
 
@@ -191,6 +403,10 @@ impl SimpleState for MenuState {
                 return Trans::Quit;
             }
 
+            if is_any_key_down(&event) {
+                self.idle_time = 0.0;
+            }
+
             // toggle previous menu item
             if is_key_down(&event, VirtualKeyCode::Up) || is_key_down(&event, VirtualKeyCode::W) {
                 self.select_previous_menu_item(world);
@@ -201,17 +417,78 @@ impl SimpleState for MenuState {
                 self.select_next_menu_item(world);
             }
 
+            // change the difficulty
+            if self.current_menu_item == MenuItem::Difficulty {
+                if is_key_down(&event, VirtualKeyCode::Left)
+                    || is_key_down(&event, VirtualKeyCode::A) {
+                    self.change_difficulty(world, false);
+                }
+                if is_key_down(&event, VirtualKeyCode::Right)
+                    || is_key_down(&event, VirtualKeyCode::D)
+                    || is_key_down(&event, VirtualKeyCode::Return) {
+                    self.change_difficulty(world, true);
+                }
+                return Trans::None;
+            }
+
+            // change the side of the human in single player matches
+            if self.current_menu_item == MenuItem::Side {
+                if is_key_down(&event, VirtualKeyCode::Left)
+                    || is_key_down(&event, VirtualKeyCode::A)
+                    || is_key_down(&event, VirtualKeyCode::Right)
+                    || is_key_down(&event, VirtualKeyCode::D)
+                    || is_key_down(&event, VirtualKeyCode::Return) {
+                    self.change_side(world);
+                }
+                return Trans::None;
+            }
+
+            // change the player using the mouse
+            if self.current_menu_item == MenuItem::Mouse {
+                if is_key_down(&event, VirtualKeyCode::Left)
+                    || is_key_down(&event, VirtualKeyCode::A) {
+                    self.change_mouse_player(world, false);
+                }
+                if is_key_down(&event, VirtualKeyCode::Right)
+                    || is_key_down(&event, VirtualKeyCode::D)
+                    || is_key_down(&event, VirtualKeyCode::Return) {
+                    self.change_mouse_player(world, true);
+                }
+                return Trans::None;
+            }
+
             // execute menu item command
             if is_key_down(&event, VirtualKeyCode::Return) {
-                match self.current_menu_item {
-                    MenuItem::SinglePlayer => {
-                        *world.write_resource::<GameMode>() = GameMode::SinglePlayer;
+                let human = world.read_resource::<GameConfig>().ai.human;
+                let controllers = match self.current_menu_item {
+                    MenuItem::SinglePlayer => PlayerControllers::single_player(human),
+                    MenuItem::Difficulty | MenuItem::Side | MenuItem::Mouse => {
+                        return Trans::None
                     }
-                    MenuItem::MultiPlayer => {
-                        *world.write_resource::<GameMode>() = GameMode::MultiPlayer;
+                    MenuItem::MultiPlayer => PlayerControllers::multi_player(),
+                    MenuItem::Host | MenuItem::Join => {
+                        // remove MenuState from the stack and switch to LobbyState
+                        return Trans::Switch(Box::new(LobbyState::new(
+                            self.current_menu_item == MenuItem::Host,
+                            self.scene_handle.clone(),
+                            self.game_ui_handle.clone(),
+                            self.paused_ui_handle.clone(),
+                            self.sprite_sheet_handle.clone(),
+                            self.font_handle.clone(),
+                        )));
+                    }
+                    MenuItem::Controls => {
+                        self.set_menu_visible(world, false);
+                        return Trans::Push(Box::new(ControlsState::new(
+                            self.font_handle.clone(),
+                        )));
                     }
                     MenuItem::Quit => return Trans::Quit,
                 };
+                let mouse_player = world.read_resource::<GameConfig>().mouse.player;
+                *world.write_resource::<PlayerControllers>() = controllers
+                    .with_mouse(mouse_player)
+                    .with_script(&world.read_resource::<Script>());
 
                 // remove MenuState from the stack and switch to GameState
                 return Trans::Switch(Box::new(GameState::new(
@@ -227,10 +504,71 @@ impl SimpleState for MenuState {
         // event was not of type StateEvent, so no transition is required
         Trans::None
     }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        let attract_idle_time = data.world.read_resource::<GameConfig>().attract.idle_time;
+        if attract_idle_time <= 0.0 {
+            return Trans::None;
+        }
+
+        // start the demo behind the menu once nobody pressed a key for a while
+        self.idle_time += data.world.read_resource::<Time>().delta_seconds();
+        if self.idle_time >= attract_idle_time {
+            self.idle_time = 0.0;
+            return Trans::Push(Box::new(GameState::demo(
+                self.scene_handle.clone(),
+                self.game_ui_handle.clone(),
+                self.paused_ui_handle.clone(),
+                self.sprite_sheet_handle.clone(),
+                self.font_handle.clone(),
+            )));
+        }
+
+        Trans::None
+    }
+}
+
+/// Returns whether any key was pressed.
+pub fn is_any_key_down(event: &Event) -> bool {
+    match *event {
+        Event::WindowEvent {
+            event: WindowEvent::KeyboardInput {
+                input: KeyboardInput { state: ElementState::Pressed, .. },
+                ..
+            },
+            ..
+        } => true,
+        _ => false,
+    }
 }
 
-fn set_ui_text_color(world: &mut World, entity: &Entity, color: [f32; 4]) {
+/// Sets the color of the `UiText` of the given entity, e.g. to highlight the selected menu item.
+pub fn set_ui_text_color(world: &mut World, entity: &Entity, color: [f32; 4]) {
     if let Some(text) = world.write_storage::<UiText>().get_mut(*entity) {
         text.color = color;
     }
-}
\ No newline at end of file
+}
+
+/// Returns the text of the difficulty menu item.
+fn difficulty_text(config: &GameConfig) -> String {
+    format!("Difficulty: {}", config.ai.difficulty.name())
+}
+
+/// Returns the text of the side menu item.
+fn side_text(config: &GameConfig) -> String {
+    let side = match config.ai.human {
+        Player::P1 => "Left",
+        Player::P2 => "Right",
+    };
+    format!("Side: {}", side)
+}
+
+/// Returns the text of the mouse menu item.
+fn mouse_text(config: &GameConfig) -> String {
+    let player = match config.mouse.player {
+        None => "Off",
+        Some(Player::P1) => "Player 1",
+        Some(Player::P2) => "Player 2",
+    };
+    format!("Mouse: {}", player)
+}
@@ -1,6 +1,11 @@
 pub use self::loading::LoadingState;
 
+pub mod controls;
 pub mod game;
+pub mod game_over;
 pub mod loading;
+pub mod lobby;
 pub mod menu;
 pub mod paused;
+pub mod replay;
+pub mod settings;
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use amethyst::{
     assets::Handle,
     ecs::prelude::Entity,
@@ -7,22 +9,189 @@ use amethyst::{
     },
     prelude::*,
     renderer::VirtualKeyCode,
-    ui::UiPrefab,
+    shrev::ReaderId,
+    ui::{
+        Anchor,
+        FontHandle,
+        UiPrefab,
+        UiText,
+        UiTransform,
+    },
+};
+
+use crate::resources::{
+    Command,
+    CommandChannel,
+    MatchRequest,
+};
+
+use super::{
+    menu::set_ui_text_color,
+    settings::SettingsState,
 };
 
-/// The `PauseState` stops (pauses) the `GameState` and displays a pause message to the player. This
-/// state is positioned on top of the `GameState` and is exited by pressing the escape key on the
-/// keyboard.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+enum MenuItem {
+    Resume,
+    Restart,
+    Settings,
+    MainMenu,
+    Quit,
+}
+
+impl MenuItem {
+    fn next(&self) -> Self {
+        match *self {
+            MenuItem::Resume => MenuItem::Restart,
+            MenuItem::Restart => MenuItem::Settings,
+            MenuItem::Settings => MenuItem::MainMenu,
+            MenuItem::MainMenu => MenuItem::Quit,
+            MenuItem::Quit => MenuItem::Resume,
+        }
+    }
+
+    fn previous(&self) -> Self {
+        match *self {
+            MenuItem::Resume => MenuItem::Quit,
+            MenuItem::Restart => MenuItem::Resume,
+            MenuItem::Settings => MenuItem::Restart,
+            MenuItem::MainMenu => MenuItem::Settings,
+            MenuItem::Quit => MenuItem::MainMenu,
+        }
+    }
+
+    fn text(&self) -> &'static str {
+        match *self {
+            MenuItem::Resume => "Resume",
+            MenuItem::Restart => "Restart match",
+            MenuItem::Settings => "Settings",
+            MenuItem::MainMenu => "Main menu",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// All `MenuItem`s in the order they are displayed.
+const MENU_ITEMS: [MenuItem; 5] = [
+    MenuItem::Resume,
+    MenuItem::Restart,
+    MenuItem::Settings,
+    MenuItem::MainMenu,
+    MenuItem::Quit,
+];
+
+/// The `PauseState` stops (pauses) the `GameState` and displays the pause menu to the player. This
+/// state is positioned on top of the `GameState` and is exited by selecting a menu item, or when a
+/// `Command::TogglePause` or `Command::QuitToMenu` is received via the `CommandChannel`. Quitting
+/// the game has to be confirmed.
 pub struct PausedState {
+    /// The current, selected `MenuItem`.
+    current_menu_item: MenuItem,
+
+    /// The list of `MenuItem`s, holding references to the `MenuItem` `Entity`s.
+    menu_items: HashMap<MenuItem, Entity>,
+
+    /// Whether quitting was selected once and has to be confirmed.
+    confirm_quit: bool,
+
     paused_ui: Option<Entity>,
     paused_ui_handle: Handle<UiPrefab>,
+    font_handle: FontHandle,
+
+    /// Reader for the `CommandChannel`, to receive resume and quit commands.
+    command_reader: Option<ReaderId<Command>>,
 }
 
 impl PausedState {
-    pub fn new(paused_ui_handle: Handle<UiPrefab>) -> Self {
+    pub fn new(paused_ui_handle: Handle<UiPrefab>, font_handle: FontHandle) -> Self {
         Self {
+            current_menu_item: MenuItem::Resume,
+            menu_items: HashMap::new(),
+            confirm_quit: false,
             paused_ui: None,
             paused_ui_handle,
+            font_handle,
+            command_reader: None,
+        }
+    }
+
+    /// Create the paused ui and the menu items.
+    fn create_menu(&mut self, world: &mut World) {
+        self.paused_ui = Some(world
+            .create_entity()
+            .with(self.paused_ui_handle.clone())
+            .build()
+        );
+
+        for (index, item) in MENU_ITEMS.iter().enumerate() {
+            let color = if *item == self.current_menu_item {
+                [1.0, 1.0, 1.0, 1.0]
+            } else {
+                [0.25, 0.25, 0.25, 1.0]
+            };
+
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    item.text().to_string(),
+                    Anchor::Middle,
+                    0.0, 75.0 - 50.0 * index as f32, 2.0, 300.0, 50.0,
+                ))
+                .with(UiText::new(
+                    self.font_handle.clone(),
+                    item.text().to_string(),
+                    color,
+                    30.0,
+                )).build();
+            self.menu_items.insert(*item, entity);
+        }
+    }
+
+    /// Delete the paused ui and the menu items.
+    fn delete_menu(&mut self, world: &mut World) {
+        if let Some(entity) = self.paused_ui.take() {
+            let _ = world.delete_entity(entity);
+        }
+        self.menu_items.drain().for_each(|(_, entity)| {
+            let _ = world.delete_entity(entity);
+        });
+    }
+
+    fn select_menu_item(&mut self, world: &mut World, menu_item: MenuItem) {
+        self.cancel_quit(world);
+
+        // Set current menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [0.25, 0.25, 0.25, 1.0]);
+        }
+
+        self.current_menu_item = menu_item;
+
+        // Set new menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [1.0, 1.0, 1.0, 1.0]);
+        }
+    }
+
+    /// Ask for confirmation before quitting the game.
+    fn request_quit(&mut self, world: &mut World) {
+        self.confirm_quit = true;
+        self.set_quit_text(world, "Really quit?");
+    }
+
+    /// Reset a pending request to quit the game.
+    fn cancel_quit(&mut self, world: &mut World) {
+        if self.confirm_quit {
+            self.confirm_quit = false;
+            self.set_quit_text(world, MenuItem::Quit.text());
+        }
+    }
+
+    fn set_quit_text(&self, world: &mut World, text: &str) {
+        if let Some(entity) = self.menu_items.get(&MenuItem::Quit) {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(*entity) {
+                ui_text.text = text.to_string();
+            }
         }
     }
 }
@@ -30,33 +199,98 @@ impl PausedState {
 impl SimpleState for PausedState {
     fn on_start(&mut self, data: StateData<GameData>) {
         info!("PausedState.on_start");
-        // create the paused ui
-        self.paused_ui = Some(data.world
-            .create_entity()
-            .with(self.paused_ui_handle.clone())
-            .build()
+        self.create_menu(data.world);
+
+        self.command_reader = Some(
+            data.world.write_resource::<CommandChannel>().register_reader()
         );
     }
 
     fn on_stop(&mut self, data: StateData<GameData>) {
         info!("PausedState.on_stop");
-        // delete paused ui
-        if let Some(entity) = self.paused_ui {
-            let _ = data.world.delete_entity(entity);
+        self.delete_menu(data.world);
+    }
+
+    fn on_pause(&mut self, data: StateData<GameData>) {
+        // make room for the settings
+        self.delete_menu(data.world);
+    }
+
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        self.create_menu(data.world);
+
+        // discard the commands that were published while the settings were open
+        if let Some(reader) = self.command_reader.as_mut() {
+            data.world.read_resource::<CommandChannel>().read(reader).for_each(|_| {});
         }
     }
 
-    fn handle_event(&mut self, _data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
         if let StateEvent::Window(event) = &event {
-            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
-                Trans::Quit
-            } else if is_key_down(&event, VirtualKeyCode::Space) {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+
+            // toggle previous menu item
+            if is_key_down(&event, VirtualKeyCode::Up) || is_key_down(&event, VirtualKeyCode::W) {
+                let previous = self.current_menu_item.previous();
+                self.select_menu_item(world, previous);
+            }
+
+            // toggle next menu item
+            if is_key_down(&event, VirtualKeyCode::Down) || is_key_down(&event, VirtualKeyCode::S) {
+                let next = self.current_menu_item.next();
+                self.select_menu_item(world, next);
+            }
+
+            // execute menu item command
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                let request = match self.current_menu_item {
+                    MenuItem::Resume => MatchRequest::Continue,
+                    MenuItem::Restart => MatchRequest::Restart,
+                    MenuItem::MainMenu => MatchRequest::MainMenu,
+                    MenuItem::Settings => {
+                        return Trans::Push(Box::new(SettingsState::new(
+                            self.font_handle.clone(),
+                        )));
+                    }
+                    MenuItem::Quit if self.confirm_quit => return Trans::Quit,
+                    MenuItem::Quit => {
+                        self.request_quit(world);
+                        return Trans::None;
+                    }
+                };
+
+                // hand the request down to the GameState
+                *world.write_resource::<MatchRequest>() = request;
+                return Trans::Pop;
+            }
+        }
+
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        let mut request = None;
+        if let Some(reader) = self.command_reader.as_mut() {
+            for command in data.world.read_resource::<CommandChannel>().read(reader) {
+                match command {
+                    Command::TogglePause => request = Some(MatchRequest::Continue),
+                    Command::QuitToMenu => request = Some(MatchRequest::MainMenu),
+                    _ => {}
+                }
+            }
+        }
+
+        // hand the request down to the GameState
+        match request {
+            Some(request) => {
+                *data.world.write_resource::<MatchRequest>() = request;
                 Trans::Pop
-            } else {
-                Trans::None
             }
-        } else {
-            Trans::None
+            None => Trans::None,
         }
     }
-}
\ No newline at end of file
+}
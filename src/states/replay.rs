@@ -0,0 +1,363 @@
+use amethyst::{
+    assets::{
+        Handle,
+        Prefab,
+    },
+    core::SystemBundle,
+    ecs::prelude::*,
+    input::{
+        is_close_requested,
+        is_key_down,
+    },
+    prelude::*,
+    renderer::{
+        SpriteSheetHandle,
+        VirtualKeyCode,
+    },
+    ui::{
+        Anchor,
+        FontHandle,
+        UiPrefab,
+        UiText,
+        UiTransform,
+    },
+};
+
+use crate::{
+    replay::{
+        Replay,
+        ReplayPlayback,
+    },
+    resources::{
+        GameEventChannel,
+        ScoreBoard,
+    },
+    simulation::Simulation,
+    systems::GameSystemsBundle,
+};
+
+use super::{
+    game::{
+        create_match_entities,
+        GamePrefabData,
+    },
+    menu::MenuState,
+};
+
+/// Number of seconds seeked by the arrow keys.
+const SEEK_SECONDS: f32 = 5.0;
+
+/// Number of characters of the timeline.
+const TIMELINE_LENGTH: usize = 40;
+
+/// Keys to jump to 0%, 10%, ..., 90% of the replay.
+const JUMP_KEYS: [VirtualKeyCode; 10] = [
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+];
+
+/// The `ReplayState` is a viewer for a `Replay`. The recorded commands are applied to the
+/// `Simulation` by the `ReplayPlayback`, which can be paused (space), sped up or slowed down (up
+/// and down), stepped through tick by tick (period and comma) and seeked (left and right, or the
+/// number keys to jump to a tenth of the replay). A timeline at the bottom shows the progress,
+/// together with the games and the rally.
+///
+/// Once every recorded tick was played back, the outcome is compared with the recorded one and
+/// the result is displayed. Pressing escape returns to the `MenuState`.
+pub struct ReplayState<'a, 'b> {
+    /// `State` specific dispatcher.
+    dispatcher: Option<Dispatcher<'a, 'b>>,
+
+    /// The replay to play back, until it is handed over to the `ReplayPlayback`.
+    replay: Option<Replay>,
+
+    /// All `Entity`s that belong to the replayed match.
+    entities: Vec<Entity>,
+
+    /// Labels displaying the timeline and the status of the playback.
+    timeline_label: Option<Entity>,
+    status_label: Option<Entity>,
+    help_label: Option<Entity>,
+
+    /// Label displaying the result of the verification, once the playback is finished.
+    result_label: Option<Entity>,
+
+    scene_handle: Handle<Prefab<GamePrefabData>>,
+
+    game_ui_handle: Handle<UiPrefab>,
+    paused_ui_handle: Handle<UiPrefab>,
+
+    sprite_sheet_handle: SpriteSheetHandle,
+    font_handle: FontHandle,
+}
+
+impl<'a, 'b> SimpleState for ReplayState<'a, 'b> {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        info!("ReplayState.on_start");
+        let world = data.world;
+
+        // create dispatcher
+        self.create_dispatcher(world);
+
+        // recreate the recorded match
+        if let Some(replay) = self.replay.take() {
+            let (playback, simulation) = ReplayPlayback::new(replay);
+            world.add_resource(simulation);
+            world.add_resource(playback);
+        }
+        world.add_resource(ScoreBoard::default());
+
+        self.entities = create_match_entities(
+            world,
+            &self.scene_handle,
+            &self.game_ui_handle,
+            &self.sprite_sheet_handle,
+        );
+
+        // initialise ui elements
+        let help = "Space: pause | Up/Down: speed | ,/.: step | Left/Right, 0-9: seek";
+        self.timeline_label = Some(self.create_label(world, "replay_timeline", 60.0, 20.0, ""));
+        self.status_label = Some(self.create_label(world, "replay_status", 90.0, 20.0, ""));
+        self.help_label = Some(self.create_label(world, "replay_help", 25.0, 15.0, help));
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        info!("ReplayState.on_stop");
+        if let Err(error) = data.world.delete_entities(&self.entities) {
+            error!("Failed to delete replay entities: {:?}", error);
+        }
+        self.entities.clear();
+
+        let labels = [
+            self.timeline_label.take(),
+            self.status_label.take(),
+            self.help_label.take(),
+            self.result_label.take(),
+        ];
+        for entity in labels.iter().filter_map(|&entity| entity) {
+            let _ = data.world.delete_entity(entity);
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                // remove ReplayState from the stack and switch to MenuState
+                return Trans::Switch(Box::new(MenuState::new(
+                    self.scene_handle.clone(),
+                    self.game_ui_handle.clone(),
+                    self.paused_ui_handle.clone(),
+                    self.sprite_sheet_handle.clone(),
+                    self.font_handle.clone(),
+                )));
+            }
+
+            let world = data.world;
+            let mut playback = world.write_resource::<ReplayPlayback>();
+            let mut simulation = world.write_resource::<Simulation>();
+            let tick = simulation.current_tick();
+            let ticks = playback.replay().ticks;
+            let seek_ticks = (SEEK_SECONDS / simulation.tick_duration()) as u64;
+
+            // the tick to seek to, if any
+            let mut seek = None;
+
+            if is_key_down(&event, VirtualKeyCode::Space) {
+                playback.toggle_pause();
+            } else if is_key_down(&event, VirtualKeyCode::Up) {
+                playback.speed_up();
+            } else if is_key_down(&event, VirtualKeyCode::Down) {
+                playback.slow_down();
+            } else if is_key_down(&event, VirtualKeyCode::Period) {
+                // stepping pauses the playback, to look at every single tick
+                playback.pause();
+                let events = playback.step(&mut simulation);
+                world.write_resource::<GameEventChannel>().iter_write(events);
+            } else if is_key_down(&event, VirtualKeyCode::Comma) {
+                playback.pause();
+                seek = Some(tick.saturating_sub(1));
+            } else if is_key_down(&event, VirtualKeyCode::Left) {
+                seek = Some(tick.saturating_sub(seek_ticks));
+            } else if is_key_down(&event, VirtualKeyCode::Right) {
+                seek = Some(tick + seek_ticks);
+            } else if let Some(index) = JUMP_KEYS.iter().position(|&key| is_key_down(&event, key)) {
+                seek = Some(ticks * index as u64 / JUMP_KEYS.len() as u64);
+            }
+
+            if let Some(target) = seek {
+                playback.seek(&mut simulation, target);
+
+                // the score only follows the points scored while playing, so it's taken over
+                *world.write_resource::<ScoreBoard>() = simulation.score().clone();
+            }
+        }
+
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        if let Some(dispatcher) = self.dispatcher.as_mut() {
+            dispatcher.dispatch(&data.world.res);
+        }
+
+        let (timeline, status, result) = {
+            let simulation = data.world.read_resource::<Simulation>();
+            let playback = data.world.read_resource::<ReplayPlayback>();
+            let score_board = simulation.score();
+            let rally = simulation.rally();
+
+            let status = format!(
+                "{} {}x | Games {} - {} | Rally {} (longest {})",
+                if playback.is_paused() { "Paused" } else { "Playing" },
+                playback.speed(),
+                score_board.games_left,
+                score_board.games_right,
+                rally.length,
+                rally.longest,
+            );
+
+            // verify the outcome once every recorded tick was played back
+            let finished = playback.is_finished(simulation.current_tick());
+            let result = if finished && self.result_label.is_none() {
+                Some(playback.replay().verify(&simulation))
+            } else {
+                None
+            };
+
+            (timeline_text(&simulation, &playback), status, result)
+        };
+        set_label_text(data.world, self.timeline_label, timeline);
+        set_label_text(data.world, self.status_label, status);
+
+        if let Some(result) = result {
+            let text = match result {
+                Ok(()) => {
+                    info!("Replay verified");
+                    "Replay verified".to_string()
+                }
+                Err(error) => {
+                    error!("Replay diverged: {}", error);
+                    format!("Replay diverged: {}", error)
+                }
+            };
+            self.result_label = Some(
+                self.create_label(data.world, "replay_result", 120.0, 25.0, &text),
+            );
+        }
+
+        Trans::None
+    }
+}
+
+impl<'a, 'b> ReplayState<'a, 'b> {
+    pub fn new(
+        replay: Replay,
+        scene_handle: Handle<Prefab<GamePrefabData>>,
+        game_ui_handle: Handle<UiPrefab>,
+        paused_ui_handle: Handle<UiPrefab>,
+        sprite_sheet_handle: SpriteSheetHandle,
+        font_handle: FontHandle,
+    ) -> Self {
+        Self {
+            dispatcher: None,
+            replay: Some(replay),
+            entities: Vec::new(),
+            timeline_label: None,
+            status_label: None,
+            help_label: None,
+            result_label: None,
+            scene_handle,
+            game_ui_handle,
+            paused_ui_handle,
+            sprite_sheet_handle,
+            font_handle,
+        }
+    }
+
+    /// Creates the `State` specific `Dispatcher`, playing back the replay instead of running the
+    /// controllers.
+    fn create_dispatcher(&mut self, world: &mut World) {
+        if self.dispatcher.is_none() {
+            let mut dispatcher_builder = DispatcherBuilder::new();
+            GameSystemsBundle::replay()
+                .build(&mut dispatcher_builder)
+                .expect("Failed to register GameSystemsBundle");
+
+            let mut dispatcher = dispatcher_builder.build();
+            dispatcher.setup(&mut world.res);
+            self.dispatcher = Some(dispatcher);
+        }
+    }
+
+    /// Creates a label at the bottom of the screen.
+    fn create_label(
+        &self,
+        world: &mut World,
+        id: &str,
+        y: f32,
+        font_size: f32,
+        text: &str,
+    ) -> Entity {
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                id.to_string(),
+                Anchor::BottomMiddle,
+                0.0, y, 2.0, 900.0, 30.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                text.to_string(),
+                [1.0, 1.0, 1.0, 1.0],
+                font_size,
+            )).build()
+    }
+}
+
+/// Replaces the text of the given label, if it differs.
+fn set_label_text(world: &mut World, label: Option<Entity>, text: String) {
+    if let Some(entity) = label {
+        if let Some(ui_text) = world.write_storage::<UiText>().get_mut(entity) {
+            if ui_text.text != text {
+                ui_text.text = text;
+            }
+        }
+    }
+}
+
+/// Returns the timeline, showing the progress of the playback and the played back time.
+fn timeline_text(simulation: &Simulation, playback: &ReplayPlayback) -> String {
+    let tick = simulation.current_tick();
+    let ticks = playback.replay().ticks.max(1);
+    let position = (tick.min(ticks) * TIMELINE_LENGTH as u64 / ticks) as usize;
+    let position = position.min(TIMELINE_LENGTH - 1);
+    let bar: String = (0..TIMELINE_LENGTH)
+        .map(|index| if index == position { '|' } else { '-' })
+        .collect();
+
+    format!(
+        "{} [{}] {} | Tick {}",
+        format_time(tick, simulation.tick_duration()),
+        bar,
+        format_time(playback.replay().ticks, simulation.tick_duration()),
+        tick,
+    )
+}
+
+/// Formats the given number of ticks as minutes and seconds.
+fn format_time(ticks: u64, tick_duration: f32) -> String {
+    let seconds = ticks as f32 * tick_duration;
+    format!("{:02}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0)
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use amethyst::{
+    ecs::prelude::Entity,
+    input::{
+        is_close_requested,
+        is_key_down,
+    },
+    prelude::*,
+    renderer::VirtualKeyCode,
+    ui::{
+        Anchor,
+        FontHandle,
+        UiText,
+        UiTransform,
+    },
+};
+
+use crate::config::{
+    GameConfig,
+    RespawnRule,
+    ServeRule,
+};
+
+use super::menu::set_ui_text_color;
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+enum MenuItem {
+    PointsToWin,
+    WinByTwo,
+    BestOf,
+    ServeRule,
+    Balls,
+    Respawn,
+    Back,
+}
+
+impl MenuItem {
+    fn next(&self) -> Self {
+        match *self {
+            MenuItem::PointsToWin => MenuItem::WinByTwo,
+            MenuItem::WinByTwo => MenuItem::BestOf,
+            MenuItem::BestOf => MenuItem::ServeRule,
+            MenuItem::ServeRule => MenuItem::Balls,
+            MenuItem::Balls => MenuItem::Respawn,
+            MenuItem::Respawn => MenuItem::Back,
+            MenuItem::Back => MenuItem::PointsToWin,
+        }
+    }
+
+    fn previous(&self) -> Self {
+        match *self {
+            MenuItem::PointsToWin => MenuItem::Back,
+            MenuItem::WinByTwo => MenuItem::PointsToWin,
+            MenuItem::BestOf => MenuItem::WinByTwo,
+            MenuItem::ServeRule => MenuItem::BestOf,
+            MenuItem::Balls => MenuItem::ServeRule,
+            MenuItem::Respawn => MenuItem::Balls,
+            MenuItem::Back => MenuItem::Respawn,
+        }
+    }
+
+    /// Returns the text of the menu item, displaying the current value of the setting.
+    fn text(&self, config: &GameConfig) -> String {
+        match *self {
+            MenuItem::PointsToWin => {
+                format!("Points to win: {}", config.match_rules.points_to_win)
+            }
+            MenuItem::WinByTwo => format!(
+                "Win by two: {}",
+                if config.match_rules.win_by_two { "On" } else { "Off" },
+            ),
+            MenuItem::BestOf => format!("Best of: {}", config.match_rules.best_of),
+            MenuItem::ServeRule => format!("Serve: {}", match config.serve.rule {
+                ServeRule::Alternate => "Alternate",
+                ServeRule::Loser => "Loser",
+            }),
+            MenuItem::Balls => format!("Balls: {}", config.multi_ball.balls),
+            MenuItem::Respawn => format!("Respawn: {}", match config.multi_ball.respawn {
+                RespawnRule::Immediately => "Immediately",
+                RespawnRule::LastBall => "After last ball",
+            }),
+            MenuItem::Back => "Back".to_string(),
+        }
+    }
+
+    /// Changes the setting in the given direction (-1 or 1).
+    fn adjust(&self, config: &mut GameConfig, direction: i32) {
+        match *self {
+            MenuItem::PointsToWin => {
+                let points = config.match_rules.points_to_win as i32 + direction;
+                config.match_rules.points_to_win = points.max(1).min(99) as u32;
+            }
+            MenuItem::WinByTwo => {
+                config.match_rules.win_by_two = !config.match_rules.win_by_two;
+            }
+            MenuItem::BestOf => {
+                // only odd numbers, so a match can't end in a draw
+                let best_of = config.match_rules.best_of as i32 + 2 * direction;
+                config.match_rules.best_of = best_of.max(1).min(7) as u32;
+            }
+            MenuItem::ServeRule => {
+                config.serve.rule = match config.serve.rule {
+                    ServeRule::Alternate => ServeRule::Loser,
+                    ServeRule::Loser => ServeRule::Alternate,
+                };
+            }
+            MenuItem::Balls => {
+                let balls = config.multi_ball.balls as i32 + direction;
+                config.multi_ball.balls = balls.max(1).min(5) as u32;
+            }
+            MenuItem::Respawn => {
+                config.multi_ball.respawn = match config.multi_ball.respawn {
+                    RespawnRule::Immediately => RespawnRule::LastBall,
+                    RespawnRule::LastBall => RespawnRule::Immediately,
+                };
+            }
+            MenuItem::Back => {}
+        }
+    }
+}
+
+/// All `MenuItem`s in the order they are displayed.
+const MENU_ITEMS: [MenuItem; 7] = [
+    MenuItem::PointsToWin,
+    MenuItem::WinByTwo,
+    MenuItem::BestOf,
+    MenuItem::ServeRule,
+    MenuItem::Balls,
+    MenuItem::Respawn,
+    MenuItem::Back,
+];
+
+/// The `SettingsState` lets the players change the match rules stored in the `GameConfig`
+/// resource. It is pushed on top of the `PausedState`, the changes apply to the next match.
+pub struct SettingsState {
+    /// The current, selected `MenuItem`.
+    current_menu_item: MenuItem,
+
+    /// The list of `MenuItem`s, holding references to the `MenuItem` `Entity`s.
+    menu_items: HashMap<MenuItem, Entity>,
+
+    /// Labels displaying the title and a hint.
+    labels: Vec<Entity>,
+
+    font_handle: FontHandle,
+}
+
+impl SettingsState {
+    pub fn new(font_handle: FontHandle) -> Self {
+        Self {
+            current_menu_item: MenuItem::PointsToWin,
+            menu_items: HashMap::new(),
+            labels: Vec::new(),
+            font_handle,
+        }
+    }
+
+    fn select_menu_item(&mut self, world: &mut World, menu_item: MenuItem) {
+        // Set current menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [0.25, 0.25, 0.25, 1.0]);
+        }
+
+        self.current_menu_item = menu_item;
+
+        // Set new menu item color.
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            set_ui_text_color(world, entity, [1.0, 1.0, 1.0, 1.0]);
+        }
+    }
+
+    /// Adjusts the setting of the current menu item and updates its text.
+    fn adjust_menu_item(&self, world: &mut World, direction: i32) {
+        let text = {
+            let mut config = world.write_resource::<GameConfig>();
+            self.current_menu_item.adjust(&mut config, direction);
+            self.current_menu_item.text(&config)
+        };
+
+        if let Some(entity) = self.menu_items.get(&self.current_menu_item) {
+            if let Some(ui_text) = world.write_storage::<UiText>().get_mut(*entity) {
+                ui_text.text = text;
+            }
+        }
+    }
+
+    fn create_text(
+        &self,
+        world: &mut World,
+        id: &str,
+        y: f32,
+        text: String,
+        color: [f32; 4],
+        font_size: f32,
+    ) -> Entity {
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                id.to_string(),
+                Anchor::Middle,
+                0.0, y, 2.0, 500.0, 50.0,
+            ))
+            .with(UiText::new(
+                self.font_handle.clone(),
+                text,
+                color,
+                font_size,
+            )).build()
+    }
+}
+
+impl SimpleState for SettingsState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        info!("SettingsState.on_start");
+        let world = data.world;
+
+        let white = [1.0, 1.0, 1.0, 1.0];
+        let grey = [0.25, 0.25, 0.25, 1.0];
+        self.labels = vec![
+            self.create_text(world, "settings", 150.0, "Settings".to_string(), white, 40.0),
+            self.create_text(
+                world, "settings_hint", -200.0,
+                "Changes apply to the next match".to_string(), grey, 20.0,
+            ),
+        ];
+
+        let texts: Vec<String> = {
+            let config = world.read_resource::<GameConfig>();
+            MENU_ITEMS.iter().map(|item| item.text(&config)).collect()
+        };
+        for (index, (item, text)) in MENU_ITEMS.iter().zip(texts).enumerate() {
+            let color = if *item == self.current_menu_item { white } else { grey };
+            let id = format!("settings_{}", index);
+            let y = 105.0 - 40.0 * index as f32;
+            let entity = self.create_text(world, &id, y, text, color, 30.0);
+            self.menu_items.insert(*item, entity);
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        info!("SettingsState.on_stop");
+        self.menu_items.values().chain(self.labels.iter()).for_each(|&entity| {
+            let _ = data.world.delete_entity(entity);
+        })
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        let world = data.world;
+
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) {
+                return Trans::Quit;
+            }
+
+            // back to the pause menu
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return Trans::Pop;
+            }
+
+            // toggle previous menu item
+            if is_key_down(&event, VirtualKeyCode::Up) || is_key_down(&event, VirtualKeyCode::W) {
+                let previous = self.current_menu_item.previous();
+                self.select_menu_item(world, previous);
+            }
+
+            // toggle next menu item
+            if is_key_down(&event, VirtualKeyCode::Down) || is_key_down(&event, VirtualKeyCode::S) {
+                let next = self.current_menu_item.next();
+                self.select_menu_item(world, next);
+            }
+
+            // change the selected setting
+            let left = is_key_down(&event, VirtualKeyCode::Left)
+                || is_key_down(&event, VirtualKeyCode::A);
+            let right = is_key_down(&event, VirtualKeyCode::Right)
+                || is_key_down(&event, VirtualKeyCode::D);
+            if left {
+                self.adjust_menu_item(world, -1);
+            }
+            if right {
+                self.adjust_menu_item(world, 1);
+            }
+
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                if self.current_menu_item == MenuItem::Back {
+                    return Trans::Pop;
+                }
+                self.adjust_menu_item(world, 1);
+            }
+        }
+
+        Trans::None
+    }
+}
@@ -4,12 +4,19 @@ use amethyst::{
     error::Error,
 };
 
+use super::pause_input::PauseInputSystem;
+
 /// Bundle containing all `System`s that should be running independent of any `State`.
 pub struct CoreSystemsBundle;
 
 impl<'a, 'b> SystemBundle<'a, 'b> for CoreSystemsBundle {
-    fn build(self, _dispatcher: &mut DispatcherBuilder) -> Result<(), Error> {
-        // TODO: actually add core systems
+    fn build(self, dispatcher: &mut DispatcherBuilder) -> Result<(), Error> {
+        // input systems
+        dispatcher.add(
+            PauseInputSystem::default(),
+            "pause_input_system",
+            &["input_system"],
+        );
 
         Ok(())
     }
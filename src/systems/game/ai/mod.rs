@@ -1,3 +0,0 @@
-pub use self::move_paddle::MovePaddleSystem;
-
-mod move_paddle;
\ No newline at end of file
@@ -2,62 +2,133 @@ use amethyst::{
     core::bundle::SystemBundle,
     ecs::DispatcherBuilder,
     error::Error,
-    prelude::*,
 };
 
-use crate::resources::GameMode;
-
 use super::{
-    ai::MovePaddleSystem as AiMovePaddleSystem,
-    bounce::BounceSystem,
-    move_balls::MoveBallsSystem,
+    controllers::ControllersSystem,
+    launch_ball::LaunchBallSystem,
+    mouse_cursor::MouseCursorSystem,
     move_paddles::MovePaddlesSystem,
-    player_input::PlayerInputSystem,
+    network::NetworkSystem,
+    record_replay::RecordReplaySystem,
+    replay::ReplaySystem,
+    score_ui::ScoreUiSystem,
+    step_simulation::StepSimulationSystem,
+    sync_transforms::SyncTransformsSystem,
     winner::WinnerSystem,
 };
 
-/// Bundle containing all `System`s relevant to the `GameState`.
-#[derive(Default)]
-pub struct GameSystemsBundle;
+/// Mode enumeration describes how the `Simulation` is advanced.
+enum Mode {
+    /// By the commands of the local controllers.
+    Local,
+    /// By the playback of a replay.
+    Replay,
+    /// By the commands of the local controllers, exchanged with the remote player.
+    Network,
+}
+
+/// Bundle containing all `System`s relevant to the `GameState`. For the `ReplayState`, the
+/// controllers and the stepping of the simulation are replaced by the playback of the replay.
+/// During a match over the network, the `Simulation` is stepped by the `NetSession` instead.
+pub struct GameSystemsBundle {
+    mode: Mode,
+}
+
+impl GameSystemsBundle {
+    /// Create a `GameSystemsBundle` that plays back the `ReplayPlayback` resource.
+    pub fn replay() -> Self {
+        Self { mode: Mode::Replay }
+    }
+
+    /// Create a `GameSystemsBundle` that plays a match over the network, using the `NetSession`
+    /// resource.
+    pub fn network() -> Self {
+        Self { mode: Mode::Network }
+    }
+}
+
+impl Default for GameSystemsBundle {
+    fn default() -> Self {
+        Self { mode: Mode::Local }
+    }
+}
 
 impl<'a, 'b> SystemBundle<'a, 'b> for GameSystemsBundle {
     fn build(self, dispatcher: &mut DispatcherBuilder) -> Result<(), Error> {
-        // input system
-        dispatcher.add(
-            PlayerInputSystem::default(),
-            "player_input_system",
-            &[],
-        );
+        // the simulation is either advanced by the playback of a replay, or by the controllers
+        let simulation_system = if let Mode::Replay = self.mode {
+            dispatcher.add(
+                ReplaySystem::default(),
+                "replay_system",
+                &[],
+            );
+            "replay_system"
+        } else {
+            // input systems
+            dispatcher.add(
+                MouseCursorSystem::default(),
+                "mouse_cursor_system",
+                &[],
+            );
+            dispatcher.add(
+                ControllersSystem::default(),
+                "controllers_system",
+                &["mouse_cursor_system"],
+            );
 
-        // movement systems
-        dispatcher.add(
-            MoveBallsSystem::default(),
-            "move_balls_system",
-            &[],
-        );
-        dispatcher.add(
-            MovePaddlesSystem::default(), // doesn't have to be pausable due to the EventChannel logic
-            "move_paddles_system",
-            &["player_input_system"],
-        );
-        dispatcher.add(
-            AiMovePaddleSystem::default().pausable(GameMode::SinglePlayer),
-            "ai_move_paddle_system",
-            &[],
-        );
+            // movement systems, which don't have to be pausable due to the EventChannel logic
+            dispatcher.add(
+                MovePaddlesSystem::default(),
+                "move_paddles_system",
+                &["controllers_system"],
+            );
+            dispatcher.add(
+                LaunchBallSystem::default(),
+                "launch_ball_system",
+                &["controllers_system"],
+            );
 
-        // collision systems
+            // simulation systems, matches over the network aren't recorded
+            if let Mode::Network = self.mode {
+                dispatcher.add(
+                    NetworkSystem::default(),
+                    "network_system",
+                    &["move_paddles_system", "launch_ball_system"],
+                );
+                "network_system"
+            } else {
+                dispatcher.add(
+                    StepSimulationSystem::default(),
+                    "step_simulation_system",
+                    &["move_paddles_system", "launch_ball_system"],
+                );
+                dispatcher.add(
+                    RecordReplaySystem::default(),
+                    "record_replay_system",
+                    &["step_simulation_system"],
+                );
+                "step_simulation_system"
+            }
+        };
         dispatcher.add(
-            BounceSystem::default(),
-            "bounce_system",
-            &["move_balls_system", "move_paddles_system"],
+            SyncTransformsSystem::default(),
+            "sync_transforms_system",
+            &[simulation_system],
         );
 
         // etc
         dispatcher.add(
             WinnerSystem::default(),
             "winner_system",
-            &["move_balls_system"],
+            &[simulation_system],
+        );
+
+        // ui systems
+        dispatcher.add(
+            ScoreUiSystem::default(),
+            "score_ui_system",
+            &["winner_system"],
         );
 
         Ok(())
@@ -0,0 +1,88 @@
+use amethyst::{
+    core::Time,
+    ecs::{
+        Read,
+        ReadExpect,
+        System,
+        Write,
+    },
+    input::InputHandler,
+};
+
+use crate::{
+    config::GameConfig,
+    controllers::{
+        self,
+        Controller,
+        ControllerContext,
+    },
+    resources::{
+        CommandChannel,
+        ControllerKind,
+        Gamepads,
+        MouseCursor,
+        Player,
+        PlayerControllers,
+        Script,
+    },
+    simulation::Simulation,
+};
+
+/// The Controllers system runs the `Controller` assigned to each player in the
+/// `PlayerControllers` resource, which publish their Commands via the CommandChannel. Whenever
+/// the assignment changes, a new controller is created for the player.
+#[derive(Default)]
+pub struct ControllersSystem {
+    /// The current controller of each player, together with the kind it was created from.
+    controllers: [Option<(ControllerKind, Box<dyn Controller>)>; 2],
+}
+
+impl<'s> System<'s> for ControllersSystem {
+    type SystemData = (
+        Read<'s, Time>,
+        Read<'s, InputHandler<String, String>>,
+        Read<'s, Gamepads>,
+        Read<'s, MouseCursor>,
+        Read<'s, PlayerControllers>,
+        Read<'s, Script>,
+        ReadExpect<'s, GameConfig>,
+        ReadExpect<'s, Simulation>,
+        Write<'s, CommandChannel>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            time,
+            input,
+            gamepads,
+            cursor,
+            assignment,
+            script,
+            config,
+            simulation,
+            mut commands,
+        ): Self::SystemData,
+    ) {
+        let context = ControllerContext {
+            input: &input,
+            gamepads: &gamepads,
+            cursor: &cursor,
+            config: &config,
+            script: &script,
+            simulation: &simulation,
+            delta_seconds: time.delta_seconds(),
+        };
+
+        for (slot, &player) in self.controllers.iter_mut().zip([Player::P1, Player::P2].iter()) {
+            let kind = assignment.get(player);
+            if slot.as_ref().map_or(true, |(current, _)| *current != kind) {
+                *slot = Some((kind, controllers::create(kind, player)));
+            }
+
+            if let Some((_, controller)) = slot.as_mut() {
+                controller.update(&context, &mut commands);
+            }
+        }
+    }
+}
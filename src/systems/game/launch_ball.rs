@@ -0,0 +1,46 @@
+use amethyst::{
+    ecs::{
+        prelude::*,
+        Read,
+        Resources,
+        System,
+        Write,
+    },
+    shrev::ReaderId,
+};
+
+use crate::{
+    resources::{
+        Command,
+        CommandChannel,
+    },
+    simulation::Inputs,
+};
+
+/// The LaunchBallSystem handles the serving of balls, depending on received commands via
+/// CommandChannel. The launch request is stored in the `Inputs` of the next `Simulation` step.
+#[derive(Default)]
+pub struct LaunchBallSystem {
+    command_reader: Option<ReaderId<Command>>
+}
+
+impl<'s> System<'s> for LaunchBallSystem {
+    type SystemData = (
+        Read<'s, CommandChannel>,
+        Write<'s, Inputs>,
+    );
+
+    fn run(&mut self, (commands, mut inputs): Self::SystemData) {
+        for command in commands.read(self.command_reader.as_mut().unwrap()) {
+            if let Command::LaunchBall(player) = command {
+                inputs.set_launch(*player);
+            }
+        }
+    }
+
+    /// Register reader for the CommandChannel.
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.command_reader = Some(res.fetch_mut::<CommandChannel>().register_reader());
+    }
+}
@@ -1,9 +1,14 @@
 pub use self::bundle::GameSystemsBundle;
 
-mod ai;
-mod bounce;
 mod bundle;
-mod move_balls;
+mod controllers;
+mod launch_ball;
+mod mouse_cursor;
 mod move_paddles;
-mod player_input;
+mod network;
+mod record_replay;
+mod replay;
+mod score_ui;
+mod step_simulation;
+mod sync_transforms;
 mod winner;
@@ -0,0 +1,56 @@
+use amethyst::{
+    core::{
+        math::Vector4,
+        transform::Transform,
+    },
+    ecs::prelude::{
+        Join,
+        Read,
+        ReadExpect,
+        ReadStorage,
+        System,
+        Write,
+    },
+    input::InputHandler,
+    renderer::{
+        Camera,
+        ScreenDimensions,
+    },
+};
+
+use crate::resources::MouseCursor;
+
+/// The MouseCursor system converts the position of the mouse cursor from window coordinates to
+/// arena units, by reversing the projection of the camera, and stores it in the `MouseCursor`
+/// resource.
+#[derive(Default)]
+pub struct MouseCursorSystem;
+
+impl<'s> System<'s> for MouseCursorSystem {
+    type SystemData = (
+        Read<'s, InputHandler<String, String>>,
+        ReadExpect<'s, ScreenDimensions>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, Transform>,
+        Write<'s, MouseCursor>,
+    );
+
+    fn run(&mut self, (input, screen, cameras, transforms, mut cursor): Self::SystemData) {
+        cursor.position = input.mouse_position().and_then(|(x, y)| {
+            let (camera, transform) = (&cameras, &transforms).join().next()?;
+            let camera: &Camera = camera;
+            let transform: &Transform = transform;
+
+            // window coordinates start at the top left, normalized device coordinates range from
+            // -1.0 to 1.0 with the origin at the centre
+            let device = Vector4::new(
+                2.0 * x as f32 / screen.width() - 1.0,
+                1.0 - 2.0 * y as f32 / screen.height(),
+                0.0,
+                1.0,
+            );
+            let world = transform.matrix() * camera.proj.try_inverse()? * device;
+            Some([world.x, world.y])
+        });
+    }
+}
@@ -1,12 +1,10 @@
 use amethyst::{
-    core::Transform,
     ecs::{
         prelude::*,
         Read,
-        ReadExpect,
         Resources,
         System,
-        WriteStorage,
+        Write,
     },
     shrev::ReaderId,
 };
@@ -15,17 +13,13 @@ use crate::{
     resources::{
         Command,
         CommandChannel,
-        Player,
-        Players
     },
-    config::{
-        ArenaConfig,
-        PaddleConfig,
-    }
+    simulation::Inputs,
 };
 
-/// The MovePaddleSystem handles the moving of paddles on the X axis, depending on received
-/// commands via CommandChannel.
+/// The MovePaddleSystem handles the moving of paddles on the Y axis, depending on received
+/// commands via CommandChannel. The requested movement is stored in the `Inputs` of the next
+/// `Simulation` step.
 #[derive(Default)]
 pub struct MovePaddlesSystem {
     command_reader: Option<ReaderId<Command>>
@@ -34,46 +28,18 @@ pub struct MovePaddlesSystem {
 impl<'s> System<'s> for MovePaddlesSystem {
     type SystemData = (
         Read<'s, CommandChannel>,
-        Read<'s, ArenaConfig>,
-        Read<'s, PaddleConfig>,
-        ReadExpect<'s, Players>,
-        WriteStorage<'s, Transform>,
+        Write<'s, Inputs>,
     );
 
-    fn run(&mut self, (
-        commands,
-        arena_config,
-        paddle_config,
-        players,
-        mut transforms
-    ): Self::SystemData) {
-
+    fn run(&mut self, (commands, mut inputs): Self::SystemData) {
         for command in commands.read(self.command_reader.as_mut().unwrap()) {
             match command {
-                // handle movement commands for player 1
-                Command::MovePaddle(Player::P1, movement) => {
-                    if let Some(transform) = transforms.get_mut(players.p1) {
-                        let new_y = calculate_y(
-                            arena_config.height,
-                            paddle_config.height,
-                            *movement,
-                            transform.translation().y
-                        );
-                        transform.set_translation_y(new_y);
-                    }
-                },
-                // handle movement commands for player 2
-                Command::MovePaddle(Player::P2, movement) => {
-                    if let Some(transform) = transforms.get_mut(players.p2) {
-                        let new_y = calculate_y(
-                            arena_config.height,
-                            paddle_config.height,
-                            *movement,
-                            transform.translation().y
-                        );
-                        transform.set_translation_y(new_y);
-                    }
+                // handle movement commands for both players, no matter whether they are issued by
+                // a human or the ai, limited to the paddle speed
+                Command::MovePaddle(player, movement) => {
+                    inputs.set_movement(*player, movement.max(-1.0).min(1.0));
                 }
+                _ => {}
             }
         }
     }
@@ -84,11 +50,3 @@ impl<'s> System<'s> for MovePaddlesSystem {
         self.command_reader = Some(res.fetch_mut::<CommandChannel>().register_reader());
     }
 }
-
-/// Calculates the new y position of the paddle based on the movement value and the current y.
-fn calculate_y(arena_height: f32, paddle_height: f32, movement: f32, current_y: f32) -> f32 {
-    let scaled_movement = 1.2 * movement as f32;
-    (current_y + scaled_movement)
-        .min(arena_height - paddle_height * 0.5)
-        .max(paddle_height * 0.5)
-}
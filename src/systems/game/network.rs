@@ -0,0 +1,59 @@
+use amethyst::{
+    core::Time,
+    ecs::prelude::{
+        Read,
+        System,
+        Write,
+        WriteExpect,
+    },
+};
+
+use crate::{
+    net::{
+        NetInput,
+        NetSession,
+    },
+    resources::{
+        GameEventChannel,
+        Rally,
+    },
+    simulation::{
+        Inputs,
+        Simulation,
+    },
+};
+
+/// The Network system takes the place of the `StepSimulationSystem` during a match over the
+/// network. The inputs of the local player take the usual way from their controller to the
+/// `Inputs`, which are handed to the `NetSession` to exchange them with the remote player and
+/// advance the `Simulation`.
+#[derive(Default)]
+pub struct NetworkSystem;
+
+impl<'s> System<'s> for NetworkSystem {
+    type SystemData = (
+        Read<'s, Time>,
+        Write<'s, Inputs>,
+        Write<'s, Rally>,
+        Write<'s, GameEventChannel>,
+        WriteExpect<'s, Simulation>,
+        WriteExpect<'s, NetSession>,
+    );
+
+    fn run(
+        &mut self,
+        (time, mut inputs, mut rally, mut events, mut simulation, mut session): Self::SystemData,
+    ) {
+        let tick = simulation.current_tick();
+        let input = NetInput::from_inputs(&inputs, session.local_player());
+        events.iter_write(session.update(time.delta_seconds(), input, &mut simulation));
+
+        // inputs are only valid until they were applied to a tick, so a launch requested during a
+        // frame that was too short for a tick isn't lost
+        if simulation.current_tick() != tick {
+            *inputs = Inputs::default();
+        }
+
+        *rally = simulation.rally().clone();
+    }
+}
@@ -0,0 +1,54 @@
+use amethyst::{
+    ecs::prelude::{
+        Read,
+        ReadExpect,
+        Resources,
+        System,
+        SystemData,
+        Write,
+    },
+    shrev::ReaderId,
+};
+
+use crate::{
+    replay::ReplayRecorder,
+    resources::{
+        Command,
+        CommandChannel,
+    },
+    simulation::Simulation,
+};
+
+/// The RecordReplay system records the commands received via the CommandChannel into the
+/// `Replay` of the `ReplayRecorder`, together with the ticks the `Simulation` advanced while
+/// they were applied.
+#[derive(Default)]
+pub struct RecordReplaySystem {
+    command_reader: Option<ReaderId<Command>>,
+}
+
+impl<'s> System<'s> for RecordReplaySystem {
+    type SystemData = (
+        Read<'s, CommandChannel>,
+        ReadExpect<'s, Simulation>,
+        Write<'s, ReplayRecorder>,
+    );
+
+    fn run(&mut self, (commands, simulation, mut recorder): Self::SystemData) {
+        // always read the commands, so none are left over once a recording starts
+        let commands: Vec<Command> = commands
+            .read(self.command_reader.as_mut().unwrap())
+            .cloned()
+            .collect();
+
+        if let Some(replay) = recorder.replay_mut() {
+            replay.record(simulation.current_tick(), &commands);
+        }
+    }
+
+    /// Register reader for the CommandChannel.
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.command_reader = Some(res.fetch_mut::<CommandChannel>().register_reader());
+    }
+}
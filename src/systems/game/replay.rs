@@ -0,0 +1,42 @@
+use amethyst::{
+    core::Time,
+    ecs::prelude::{
+        Read,
+        System,
+        Write,
+        WriteExpect,
+    },
+};
+
+use crate::{
+    replay::ReplayPlayback,
+    resources::{
+        GameEventChannel,
+        Rally,
+    },
+    simulation::Simulation,
+};
+
+/// The Replay system takes the place of the controllers and the `StepSimulationSystem` while a
+/// replay is played back. It advances the `Simulation` by applying the recorded commands, at the
+/// speed chosen in the `ReplayState`, and publishes what happened via the GameEventChannel.
+#[derive(Default)]
+pub struct ReplaySystem;
+
+impl<'s> System<'s> for ReplaySystem {
+    type SystemData = (
+        Read<'s, Time>,
+        Write<'s, Rally>,
+        Write<'s, GameEventChannel>,
+        WriteExpect<'s, Simulation>,
+        WriteExpect<'s, ReplayPlayback>,
+    );
+
+    fn run(
+        &mut self,
+        (time, mut rally, mut events, mut simulation, mut playback): Self::SystemData,
+    ) {
+        events.iter_write(playback.advance(time.delta_seconds(), &mut simulation));
+        *rally = simulation.rally().clone();
+    }
+}
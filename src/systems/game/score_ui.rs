@@ -0,0 +1,62 @@
+use amethyst::{
+    ecs::prelude::{
+        Join,
+        Read,
+        ReadStorage,
+        System,
+        WriteStorage,
+    },
+    ui::{
+        UiText,
+        UiTransform,
+    },
+};
+
+use crate::resources::ScoreBoard;
+
+/// Id of the `UiText` displaying the points of player 1, as defined in the game ui prefab.
+const P1_SCORE_ID: &str = "p1_score";
+
+/// Id of the `UiText` displaying the points of player 2, as defined in the game ui prefab.
+const P2_SCORE_ID: &str = "p2_score";
+
+/// Id of the `UiText` displaying the games won by player 1, as defined in the game ui prefab.
+const P1_GAMES_ID: &str = "p1_games";
+
+/// Id of the `UiText` displaying the games won by player 2, as defined in the game ui prefab.
+const P2_GAMES_ID: &str = "p2_games";
+
+/// The ScoreUi system displays the points and games of the `ScoreBoard` using the ui texts of
+/// the game ui prefab, which defines their layout and style. Without those ui elements, e.g. in a
+/// headless match, nothing is displayed.
+#[derive(Default)]
+pub struct ScoreUiSystem;
+
+impl<'s> System<'s> for ScoreUiSystem {
+    type SystemData = (
+        Read<'s, ScoreBoard>,
+        ReadStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (score_board, ui_transforms, mut ui_texts): Self::SystemData) {
+        for (transform, ui_text) in (&ui_transforms, &mut ui_texts).join() {
+            let transform: &UiTransform = transform;
+            let ui_text: &mut UiText = ui_text;
+
+            let text = match transform.id.as_str() {
+                P1_SCORE_ID => score_board.score_left.to_string(),
+                P2_SCORE_ID => score_board.score_right.to_string(),
+                P1_GAMES_ID => format!("Games {}", score_board.games_left),
+                P2_GAMES_ID => format!("Games {}", score_board.games_right),
+                _ => continue,
+            };
+
+            // the prefab may be loaded after the score changed, so the text is checked every
+            // frame, but only replaced when it differs
+            if ui_text.text != text {
+                ui_text.text = text;
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use amethyst::{
+    core::timing::Time,
+    ecs::prelude::{
+        Read,
+        System,
+        Write,
+        WriteExpect,
+    },
+};
+
+use crate::{
+    resources::{
+        GameEventChannel,
+        Rally,
+    },
+    simulation::{
+        Inputs,
+        Simulation,
+    },
+};
+
+/// The StepSimulation system advances the `Simulation` according to the amount of time passed
+/// between frames, using the `Inputs` collected by the input and ai systems. Afterwards the
+/// current `Rally` is published as a resource and the `GameEvent`s of the step are published via
+/// the GameEventChannel.
+#[derive(Default)]
+pub struct StepSimulationSystem;
+
+impl<'s> System<'s> for StepSimulationSystem {
+    type SystemData = (
+        Read<'s, Time>,
+        Write<'s, Inputs>,
+        Write<'s, Rally>,
+        Write<'s, GameEventChannel>,
+        WriteExpect<'s, Simulation>,
+    );
+
+    fn run(
+        &mut self,
+        (time, mut inputs, mut rally, mut events, mut simulation): Self::SystemData,
+    ) {
+        let tick = simulation.current_tick();
+        simulation.step(time.delta_seconds(), &inputs);
+        events.iter_write(simulation.events().iter().cloned());
+
+        // inputs are only valid until they were applied to a tick, so a launch requested during a
+        // frame that was too short for a tick isn't lost
+        if simulation.current_tick() != tick {
+            *inputs = Inputs::default();
+        }
+
+        *rally = simulation.rally().clone();
+    }
+}
@@ -0,0 +1,69 @@
+use amethyst::{
+    core::transform::Transform,
+    ecs::prelude::{
+        Entities,
+        Join,
+        ReadExpect,
+        ReadStorage,
+        System,
+        WriteStorage,
+    },
+    renderer::Hidden,
+};
+
+use crate::{
+    components::Ball,
+    resources::{
+        Player,
+        Players,
+    },
+    simulation::Simulation,
+};
+
+/// The SyncTransforms system copies the positions of the paddles and balls from the `Simulation`
+/// to the `Transform`s of their entities, so they are rendered at the right place. Balls which
+/// were removed from play are hidden.
+#[derive(Default)]
+pub struct SyncTransformsSystem;
+
+impl<'s> System<'s> for SyncTransformsSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, Simulation>,
+        ReadExpect<'s, Players>,
+        ReadStorage<'s, Ball>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Hidden>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, simulation, players, balls, mut transforms, mut hidden): Self::SystemData,
+    ) {
+        // position the paddles
+        for (player, entity) in [(Player::P1, players.p1), (Player::P2, players.p2)].iter() {
+            if let Some(transform) = transforms.get_mut(*entity) {
+                let [x, y] = simulation.paddle(*player).position;
+                transform.set_translation_x(x);
+                transform.set_translation_y(y);
+            }
+        }
+
+        // position the balls
+        for (entity, ball, transform) in (&entities, &balls, &mut transforms).join() {
+            let ball: &Ball = ball;
+            let transform: &mut Transform = transform;
+
+            if let Some(body) = simulation.balls().get(ball.index) {
+                transform.set_translation_x(body.position[0]);
+                transform.set_translation_y(body.position[1]);
+
+                if body.removed {
+                    let _ = hidden.insert(entity, Hidden);
+                } else {
+                    hidden.remove(entity);
+                }
+            }
+        }
+    }
+}
@@ -1,84 +1,80 @@
 use amethyst::{
-    core::transform::Transform,
     ecs::prelude::{
-        Join,
         Read,
         ReadExpect,
+        Resources,
         System,
+        SystemData,
         Write,
-        WriteStorage,
     },
-    ui::UiText,
+    shrev::ReaderId,
 };
 
 use crate::{
-    components::Ball,
-    config::ArenaConfig,
+    net::NetSession,
     resources::{
+        GameEvent,
+        GameEventChannel,
         ScoreBoard,
-        ScoreText,
     },
+    simulation::Simulation,
 };
 
+/// The Winner system reacts to points scored during the last `Simulation` step, received via the
+/// GameEventChannel, by updating the `ScoreBoard`. Displaying the score is up to the
+/// `ScoreUiSystem`. Over the network, the score is taken from the confirmed `Simulation` of the
+/// `NetSession`, as the predicted one may still change.
 #[derive(Default)]
-pub struct WinnerSystem;
+pub struct WinnerSystem {
+    event_reader: Option<ReaderId<GameEvent>>,
+}
 
 impl<'s> System<'s> for WinnerSystem {
     type SystemData = (
-        Read<'s, ArenaConfig>,
-        ReadExpect<'s, ScoreText>,
-        WriteStorage<'s, Ball>,
-        WriteStorage<'s, Transform>,
-        WriteStorage<'s, UiText>,
+        Read<'s, GameEventChannel>,
+        ReadExpect<'s, Simulation>,
+        Option<Read<'s, NetSession>>,
         Write<'s, ScoreBoard>,
     );
 
     fn run(&mut self, (
-        arena_config,
-        score_text,
-        mut balls,
-        mut transforms,
-        mut ui_text,
+        events,
+        simulation,
+        session,
         mut score_board
     ): Self::SystemData) {
-        for (ball, transform) in (&mut balls, &mut transforms).join() {
-            let ball: &mut Ball = ball;
-            let transform: &mut Transform = transform;
-
-            let ball_x = transform.translation().x;
-
-            let did_hit = if ball_x <= ball.radius {
-                // Right player scored on the left side.
-                // We top the score at 999 to avoid text overlap.
-                score_board.score_right = (score_board.score_right + 1).min(999);
-
-                if let Some(text) = ui_text.get_mut(score_text.p2_score) {
-                    text.text = score_board.score_right.to_string();
-                }
-                true
-            } else if ball_x >= arena_config.width - ball.radius {
-                // Left player scored on the right side.
-                // We top the score at 999 to avoid text overlap.
-                score_board.score_left = (score_board.score_left + 1).min(999);
-
-                if let Some(text) = ui_text.get_mut(score_text.p1_score) {
-                    text.text = score_board.score_left.to_string();
-                }
-                true
-            } else {
-                false
-            };
+        let points = events
+            .read(self.event_reader.as_mut().unwrap())
+            .filter(|event| match event {
+                GameEvent::PointScored { .. } => true,
+                _ => false,
+            })
+            .count();
+        if points == 0 {
+            return;
+        }
 
-            if did_hit {
-                ball.velocity[0] = -ball.velocity[0]; // Reverse Direction
-                transform.set_translation_x(arena_config.width / 2.0); // Reset Position
+        let simulation = match session.as_ref() {
+            Some(session) => session.confirmed(),
+            None => &*simulation,
+        };
+        *score_board = simulation.score().clone();
 
-                // Print the score board.
-                info!("Score: | {:^3} | {:^3} |",
-                      score_board.score_left,
-                      score_board.score_right
-                );
-            }
+        // Print the score board.
+        info!("Score: | {:^3} | {:^3} | Games: | {:^3} | {:^3} |",
+              score_board.score_left,
+              score_board.score_right,
+              score_board.games_left,
+              score_board.games_right
+        );
+        if simulation.is_deuce() {
+            info!("Deuce");
         }
     }
-}
\ No newline at end of file
+
+    /// Register reader for the GameEventChannel.
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.event_reader = Some(res.fetch_mut::<GameEventChannel>().register_reader());
+    }
+}
@@ -0,0 +1,61 @@
+use amethyst::ecs::{
+    ReadExpect,
+    System,
+    Write,
+};
+
+use crate::{
+    config::GameConfig,
+    gamepad::GamepadSource,
+    resources::{
+        Command,
+        CommandChannel,
+        GamepadEvent,
+        Gamepads,
+        PlayerControllers,
+    },
+};
+
+/// GamepadInput system polls the `GamepadSource` and keeps the `Gamepads` resource up to date,
+/// including the assignment of gamepads to players when they are connected or disconnected. Like
+/// the `PauseInputSystem`, it converts the pause button of any gamepad into Commands, independent
+/// of any `State`.
+///
+/// As some gamepad libraries can't be shared between threads, the system has to be added as a
+/// thread local system.
+pub struct GamepadInputSystem {
+    source: Box<dyn GamepadSource>,
+    events: Vec<GamepadEvent>,
+}
+
+impl GamepadInputSystem {
+    pub fn new(source: Box<dyn GamepadSource>) -> Self {
+        Self {
+            source,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<'s> System<'s> for GamepadInputSystem {
+    type SystemData = (
+        ReadExpect<'s, GameConfig>,
+        Write<'s, Gamepads>,
+        Write<'s, PlayerControllers>,
+        Write<'s, CommandChannel>,
+    );
+
+    fn run(&mut self, (config, mut gamepads, mut controllers, mut commands): Self::SystemData) {
+        gamepads.begin_frame();
+        self.source.poll(&mut self.events);
+        for event in self.events.drain(..) {
+            gamepads.handle_event(&event);
+        }
+        gamepads.update_assignments(&mut controllers);
+
+        let pause_button = config.gamepad.pause_button;
+        if gamepads.pads().any(|pad| pad.was_pressed(pause_button)) {
+            commands.single_write(Command::TogglePause);
+        }
+    }
+}
@@ -1,7 +1,10 @@
 pub use self::{
     bundle::CoreSystemsBundle,
     game::GameSystemsBundle,
+    gamepad_input::GamepadInputSystem,
 };
 
 mod bundle;
-mod game;
\ No newline at end of file
+mod game;
+mod gamepad_input;
+mod pause_input;
\ No newline at end of file
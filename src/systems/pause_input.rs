@@ -0,0 +1,46 @@
+use amethyst::{
+    ecs::{
+        Read,
+        System,
+        Write,
+    },
+    input::InputHandler,
+};
+
+use crate::resources::{
+    Command,
+    CommandChannel,
+};
+
+/// Actions that control the flow of the game, and the `Command` they are converted into.
+const ACTIONS: [(&str, Command); 2] = [
+    ("pause", Command::TogglePause),
+    ("quit_to_menu", Command::QuitToMenu),
+];
+
+/// PauseInput system converts the pause and quit-to-menu actions into Commands, which are
+/// published via the CommandChannel and interpreted by the `State`s. It runs independent of any
+/// `State`, so the same pause action also resumes the game while paused. A Command is only
+/// published when the action is pressed, not while it is held down.
+#[derive(Default)]
+pub struct PauseInputSystem {
+    /// Whether the action at the same index of `ACTIONS` was down during the last frame.
+    was_down: [bool; 2],
+}
+
+impl<'s> System<'s> for PauseInputSystem {
+    type SystemData = (
+        Read<'s, InputHandler<String, String>>,
+        Write<'s, CommandChannel>,
+    );
+
+    fn run(&mut self, (input, mut commands): Self::SystemData) {
+        for (index, (action, command)) in ACTIONS.iter().enumerate() {
+            let is_down = input.action_is_down(action).unwrap_or(false);
+            if is_down && !self.was_down[index] {
+                commands.single_write(command.clone());
+            }
+            self.was_down[index] = is_down;
+        }
+    }
+}